@@ -0,0 +1,148 @@
+//! 告警写入系统日志 (--log-alerts)
+//! 告警触发 / 解除时写一条结构化消息，方便无人值守时事后追查。
+//!
+//! - Unix: 以 RFC 3164 格式发送到本机 syslog 套接字 (/dev/log、/var/run/syslog 等)
+//! - Windows: 通过 ReportEventW 写入 "Application" 事件日志，来源为 `winload`
+//!   (需要 `eventlog` feature)
+//!
+//! 日志设施不可用时 [`AlertLog::open`] 返回错误，调用方只需提示并继续运行；
+//! 之后单条消息写入失败会被静默忽略，不影响主循环。
+
+use std::io;
+
+/// 告警事件的级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertLevel {
+    /// 告警触发
+    Warning,
+    /// 告警解除
+    Info,
+}
+
+#[cfg(unix)]
+pub use unix::AlertLog;
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::os::unix::net::UnixDatagram;
+
+    /// 常见的本机 syslog 套接字路径 (Linux / macOS / BSD)
+    const SYSLOG_PATHS: [&str; 3] = ["/dev/log", "/var/run/syslog", "/var/run/log"];
+
+    /// syslog facility: LOG_DAEMON (3)
+    const FACILITY_DAEMON: u8 = 3;
+
+    /// 写入 syslog 的告警日志
+    pub struct AlertLog {
+        sock: UnixDatagram,
+    }
+
+    impl AlertLog {
+        /// 连接本机 syslog 套接字
+        pub fn open() -> io::Result<Self> {
+            let sock = UnixDatagram::unbound()?;
+            if SYSLOG_PATHS.iter().any(|path| sock.connect(path).is_ok()) {
+                sock.set_nonblocking(true)?;
+                return Ok(Self { sock });
+            }
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no syslog socket found (tried {})", SYSLOG_PATHS.join(", ")),
+            ))
+        }
+
+        /// 写入一条消息，失败时忽略
+        pub fn log(&mut self, level: AlertLevel, msg: &str) {
+            // 严重级别: LOG_WARNING (4) / LOG_INFO (6)
+            let severity = match level {
+                AlertLevel::Warning => 4,
+                AlertLevel::Info => 6,
+            };
+            let pri = FACILITY_DAEMON * 8 + severity;
+            let line = format!("<{pri}>winload[{}]: {msg}", std::process::id());
+            let _ = self.sock.send(line.as_bytes());
+        }
+    }
+}
+
+#[cfg(all(windows, feature = "eventlog"))]
+pub use windows::AlertLog;
+
+#[cfg(all(windows, feature = "eventlog"))]
+mod windows {
+    use super::*;
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::System::EventLog::{
+        DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_INFORMATION_TYPE,
+        EVENTLOG_WARNING_TYPE,
+    };
+
+    /// 写入 Windows 事件日志的告警日志
+    pub struct AlertLog {
+        handle: HANDLE,
+    }
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    impl AlertLog {
+        /// 以 `winload` 为来源注册事件源
+        pub fn open() -> io::Result<Self> {
+            let source = wide("winload");
+            let handle = unsafe { RegisterEventSourceW(std::ptr::null(), source.as_ptr()) };
+            if handle.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self { handle })
+        }
+
+        /// 写入一条消息，失败时忽略
+        pub fn log(&mut self, level: AlertLevel, msg: &str) {
+            let kind = match level {
+                AlertLevel::Warning => EVENTLOG_WARNING_TYPE,
+                AlertLevel::Info => EVENTLOG_INFORMATION_TYPE,
+            };
+            let text = wide(msg);
+            let strings = [text.as_ptr()];
+            unsafe {
+                ReportEventW(
+                    self.handle,
+                    kind,
+                    0,
+                    1,
+                    std::ptr::null_mut(),
+                    1,
+                    0,
+                    strings.as_ptr(),
+                    std::ptr::null(),
+                );
+            }
+        }
+    }
+
+    impl Drop for AlertLog {
+        fn drop(&mut self) {
+            unsafe {
+                DeregisterEventSource(self.handle);
+            }
+        }
+    }
+}
+
+#[cfg(not(any(unix, all(windows, feature = "eventlog"))))]
+/// 告警日志 (当前平台 / 编译配置不支持)
+pub struct AlertLog;
+
+#[cfg(not(any(unix, all(windows, feature = "eventlog"))))]
+impl AlertLog {
+    pub fn open() -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "winload was compiled without Event Log support (feature 'eventlog' disabled)",
+        ))
+    }
+
+    pub fn log(&mut self, _level: AlertLevel, _msg: &str) {}
+}
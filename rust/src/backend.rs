@@ -0,0 +1,301 @@
+//! 计数器后端选择 (--backend)
+//! Linux 上读取网卡累计字节数有多种方式，精度与开销各不相同:
+//!
+//! - `auto`:    sysinfo (读取 `/sys/class/net/<name>/statistics`)，即默认的 [`Collector`]
+//! - `procfs`:  一次解析 `/proc/net/dev` 得到所有网卡
+//! - `netlink`: RTM_GETLINK dump，直接取内核的 64 位 `rtnl_link_stats64`
+//!
+//! 设备列表、地址与链路状态仍由 [`Collector`] 提供，只替换计数器的来源。
+//! 其他平台只有 `auto`。
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use winload::collector::{Collector, DeviceInfo, DeviceKey, Snapshot, TrafficSource};
+
+/// 网卡计数器的读取方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CounterBackend {
+    /// 平台默认 (sysinfo)
+    #[default]
+    Auto,
+    /// 解析 /proc/net/dev (仅 Linux)
+    Procfs,
+    /// rtnetlink RTM_GETLINK (仅 Linux)
+    Netlink,
+}
+
+impl CounterBackend {
+    /// 命令行中的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            CounterBackend::Auto => "auto",
+            CounterBackend::Procfs => "procfs",
+            CounterBackend::Netlink => "netlink",
+        }
+    }
+
+    /// 用于 --debug-info 的说明
+    pub fn describe(self) -> &'static str {
+        match self {
+            CounterBackend::Auto => "auto (sysinfo)",
+            CounterBackend::Procfs => "procfs (/proc/net/dev)",
+            CounterBackend::Netlink => "netlink (RTM_GETLINK)",
+        }
+    }
+
+    /// 当前平台是否支持该后端
+    pub fn available(self) -> bool {
+        self == CounterBackend::Auto || cfg!(target_os = "linux")
+    }
+}
+
+/// 按 `backend` 创建本机数据源；`auto` 即 [`Collector`]
+///
+/// 非 `auto` 的后端先试读一次，不可用时返回原因。
+pub fn open(backend: CounterBackend) -> Result<Box<dyn TrafficSource>, String> {
+    if backend == CounterBackend::Auto {
+        return Ok(Box::new(Collector::new()));
+    }
+    if !backend.available() {
+        return Err(format!("--backend {} is only available on Linux", backend.name()));
+    }
+    platform::read(backend)?;
+    Ok(Box::new(BackendSource {
+        collector: Collector::new(),
+        backend,
+        start: Instant::now(),
+        error: None,
+    }))
+}
+
+/// 设备信息来自 [`Collector`]，计数器来自指定后端的数据源
+struct BackendSource {
+    collector: Collector,
+    backend: CounterBackend,
+    start: Instant,
+    /// 最近一次读取失败的原因，成功后清除
+    error: Option<String>,
+}
+
+impl TrafficSource for BackendSource {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        self.collector.devices()
+    }
+
+    fn collect(&mut self) -> HashMap<DeviceKey, Snapshot> {
+        let elapsed_secs = self.start.elapsed().as_secs_f64();
+        match platform::read(self.backend) {
+            Ok(counters) => {
+                self.error = None;
+                counters
+                    .into_iter()
+                    .map(|(name, (bytes_recv, bytes_sent))| {
+                        let snap = Snapshot {
+                            elapsed_secs,
+                            bytes_recv,
+                            bytes_sent,
+                        };
+                        (DeviceKey::local(&name), snap)
+                    })
+                    .collect()
+            }
+            Err(e) => {
+                self.error = Some(e);
+                HashMap::new()
+            }
+        }
+    }
+
+    fn status(&self) -> Option<String> {
+        self.error.clone()
+    }
+
+    fn link_up(&self, name: &str) -> Option<bool> {
+        TrafficSource::link_up(&self.collector, name)
+    }
+
+    fn refresh_devices(&mut self) {
+        self.collector.refresh_devices();
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+//  Linux: /proc/net/dev + rtnetlink
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::CounterBackend;
+    use std::collections::HashMap;
+    use std::{fs, io, mem};
+
+    const RTM_NEWLINK: u16 = 16;
+    const RTM_GETLINK: u16 = 18;
+    const IFLA_IFNAME: u16 = 3;
+    const IFLA_STATS64: u16 = 23;
+    /// `struct nlmsghdr` 长度
+    const NLMSG_HDR_LEN: usize = 16;
+    /// `struct ifinfomsg` 长度
+    const IFINFOMSG_LEN: usize = 16;
+    /// `struct rtnl_link_stats64` 中 `rx_bytes` / `tx_bytes` 的偏移
+    const RX_BYTES_OFFSET: usize = 16;
+    const TX_BYTES_OFFSET: usize = 24;
+
+    /// 读取所有网卡的累计 (收, 发) 字节数
+    pub fn read(backend: CounterBackend) -> Result<HashMap<String, (u64, u64)>, String> {
+        match backend {
+            CounterBackend::Procfs => read_proc_net_dev(),
+            CounterBackend::Netlink => read_netlink(),
+            CounterBackend::Auto => Ok(HashMap::new()),
+        }
+    }
+
+    /// 解析 /proc/net/dev: 前两行为表头，之后每行 "name: 收 8 列 | 发 8 列"
+    fn read_proc_net_dev() -> Result<HashMap<String, (u64, u64)>, String> {
+        let text = fs::read_to_string("/proc/net/dev")
+            .map_err(|e| format!("Failed to read /proc/net/dev: {e}"))?;
+        Ok(text
+            .lines()
+            .skip(2)
+            .filter_map(|line| {
+                let (name, fields) = line.split_once(':')?;
+                let fields: Vec<u64> = fields
+                    .split_whitespace()
+                    .map(|f| f.parse().ok())
+                    .collect::<Option<_>>()?;
+                Some((name.trim().to_string(), (*fields.first()?, *fields.get(8)?)))
+            })
+            .collect())
+    }
+
+    fn u16_at(data: &[u8], off: usize) -> Option<u16> {
+        Some(u16::from_ne_bytes(data.get(off..off + 2)?.try_into().ok()?))
+    }
+
+    fn u32_at(data: &[u8], off: usize) -> Option<u32> {
+        Some(u32::from_ne_bytes(data.get(off..off + 4)?.try_into().ok()?))
+    }
+
+    fn u64_at(data: &[u8], off: usize) -> Option<u64> {
+        Some(u64::from_ne_bytes(data.get(off..off + 8)?.try_into().ok()?))
+    }
+
+    /// 发送 RTM_GETLINK dump 请求，从每条 RTM_NEWLINK 中取网卡名与 64 位计数器
+    fn read_netlink() -> Result<HashMap<String, (u64, u64)>, String> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_ROUTE,
+            )
+        };
+        if fd < 0 {
+            return Err(format!(
+                "Failed to open rtnetlink socket: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        let result = dump_links(fd);
+        unsafe { libc::close(fd) };
+        result
+    }
+
+    fn dump_links(fd: i32) -> Result<HashMap<String, (u64, u64)>, String> {
+        // nlmsghdr (16) + ifinfomsg (16)，ifi_family = AF_UNSPEC (0)
+        let mut req = [0u8; NLMSG_HDR_LEN + IFINFOMSG_LEN];
+        let len = req.len() as u32;
+        req[0..4].copy_from_slice(&len.to_ne_bytes());
+        req[4..6].copy_from_slice(&RTM_GETLINK.to_ne_bytes());
+        let flags = (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16;
+        req[6..8].copy_from_slice(&flags.to_ne_bytes());
+
+        let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as u16;
+        let sent = unsafe {
+            libc::sendto(
+                fd,
+                req.as_ptr() as *const libc::c_void,
+                req.len(),
+                0,
+                &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if sent < 0 {
+            return Err(format!("RTM_GETLINK request failed: {}", io::Error::last_os_error()));
+        }
+
+        let mut links = HashMap::new();
+        let mut buf = vec![0u8; 32 * 1024];
+        loop {
+            let n = unsafe { libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+            if n < 0 {
+                return Err(format!("rtnetlink read failed: {}", io::Error::last_os_error()));
+            }
+            let mut data = &buf[..n as usize];
+            while data.len() >= NLMSG_HDR_LEN {
+                let msg_len = u32_at(data, 0).unwrap_or(0) as usize;
+                if msg_len < NLMSG_HDR_LEN || msg_len > data.len() {
+                    break;
+                }
+                let msg_type = u16_at(data, 4).unwrap_or(0);
+                if msg_type == libc::NLMSG_DONE as u16 {
+                    return Ok(links);
+                }
+                if msg_type == libc::NLMSG_ERROR as u16 {
+                    return Err("rtnetlink returned an error for RTM_GETLINK".to_string());
+                }
+                if msg_type == RTM_NEWLINK {
+                    if let Some((name, counters)) = parse_link(&data[NLMSG_HDR_LEN..msg_len]) {
+                        links.insert(name, counters);
+                    }
+                }
+                // 消息按 4 字节对齐
+                let aligned = (msg_len + 3) & !3;
+                data = data.get(aligned..).unwrap_or_default();
+            }
+        }
+    }
+
+    /// 解析一条 RTM_NEWLINK: ifinfomsg 之后的属性中取 IFLA_IFNAME 与 IFLA_STATS64
+    fn parse_link(msg: &[u8]) -> Option<(String, (u64, u64))> {
+        let mut attrs = msg.get(IFINFOMSG_LEN..)?;
+        let mut name = None;
+        let mut counters = None;
+        while attrs.len() >= 4 {
+            let rta_len = u16_at(attrs, 0)? as usize;
+            let rta_type = u16_at(attrs, 2)?;
+            if rta_len < 4 || rta_len > attrs.len() {
+                break;
+            }
+            let payload = &attrs[4..rta_len];
+            match rta_type {
+                IFLA_IFNAME => {
+                    let end = payload.iter().position(|&b| b == 0).unwrap_or(payload.len());
+                    name = Some(String::from_utf8_lossy(&payload[..end]).into_owned());
+                }
+                IFLA_STATS64 => {
+                    counters = Some((u64_at(payload, RX_BYTES_OFFSET)?, u64_at(payload, TX_BYTES_OFFSET)?));
+                }
+                _ => {}
+            }
+            attrs = attrs.get((rta_len + 3) & !3..).unwrap_or_default();
+        }
+        Some((name?, counters?))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+//  其他平台: 只有 auto
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::CounterBackend;
+    use std::collections::HashMap;
+
+    pub fn read(_backend: CounterBackend) -> Result<HashMap<String, (u64, u64)>, String> {
+        Ok(HashMap::new())
+    }
+}
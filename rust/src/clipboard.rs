@@ -0,0 +1,63 @@
+//! 复制文本到系统剪贴板 (c 键)
+//! 调用平台自带的剪贴板命令，不引入额外依赖:
+//!
+//! - Windows: `clip`
+//! - macOS:   `pbcopy`
+//! - Linux / BSD: Wayland 下用 `wl-copy`，X11 下依次尝试 `xclip` / `xsel`
+//!
+//! 无图形会话 (如 ssh / 纯终端) 或找不到可用命令时返回错误说明，由调用方提示。
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// 把 `text` 复制到剪贴板，成功时返回所用的命令名
+pub fn copy(text: &str) -> Result<&'static str, String> {
+    let candidates = candidates();
+    if candidates.is_empty() {
+        return Err("no clipboard available (no graphical session)".to_string());
+    }
+    for (program, args) in candidates {
+        if run(program, args, text) {
+            return Ok(program);
+        }
+    }
+    Err("no clipboard tool found (install wl-clipboard, xclip or xsel)".to_string())
+}
+
+/// 当前平台可尝试的剪贴板命令
+fn candidates() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "windows") {
+        vec![("clip", &[])]
+    } else if cfg!(target_os = "macos") {
+        vec![("pbcopy", &[])]
+    } else {
+        let mut list: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            list.push(("wl-copy", &[]));
+        }
+        if std::env::var_os("DISPLAY").is_some() {
+            list.push(("xclip", &["-selection", "clipboard"]));
+            list.push(("xsel", &["--clipboard", "--input"]));
+        }
+        list
+    }
+}
+
+/// 运行剪贴板命令并通过 stdin 写入文本
+fn run(program: &str, args: &[&str], text: &str) -> bool {
+    // stdout / stderr 不接管道: xclip 等会在后台驻留以持有剪贴板内容
+    let Ok(mut child) = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    let written = child
+        .stdin
+        .take()
+        .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    child.wait().is_ok_and(|s| s.success()) && written
+}
@@ -2,8 +2,8 @@
 //! 通过 sysinfo 采集各网卡的累计收发字节数，供上层统计和绘图使用。
 
 use sysinfo::Networks;
-use std::collections::HashMap;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 /// 单次采样快照
 #[derive(Clone, Debug)]
@@ -17,25 +17,348 @@ pub struct Snapshot {
 }
 
 /// 网卡设备信息
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct DeviceInfo {
     /// 设备名称
     pub name: String,
     /// IPv4 地址列表
     pub addrs: Vec<String>,
+    /// IPv6 地址列表 (全局地址在前，链路本地地址在后)
+    pub addrs6: Vec<String>,
+    /// MAC 地址 (无硬件地址时为 None)
+    pub mac: Option<String>,
+    /// 接口类型 (ethernet / wireless / loopback / tunnel / other)，无法判断时为 None
+    pub kind: Option<String>,
+    /// 协商链路速率 (Mbit/s)，未知或链路断开时为 None
+    pub link_speed_mbps: Option<u64>,
+    /// 链路是否已启用且有载波，平台无法获取时为 None (见 [`TrafficSource::link_up`])
+    pub link_up: Option<bool>,
+    /// 操作系统分配的接口索引 (ifindex)，比名称稳定，平台无法获取时为 None
+    pub index: Option<u32>,
+    /// 是否为 VPN 隧道接口 (TUN/TAP、WireGuard、utun)，平台无法可靠判断时为 None
+    pub vpn: Option<bool>,
+}
+
+/// 设备在快照表中的键: 有接口索引时用索引，否则用名称
+///
+/// 索引由操作系统保证唯一，名称则可能重复 (如某些系统上拆分出的收 / 发伪接口)。
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DeviceKey {
+    Index(u32),
+    Name(String),
+}
+
+impl DeviceKey {
+    /// 本机网卡的键: 能读到接口索引时用索引
+    pub fn local(name: &str) -> Self {
+        read_ifindex(name).map_or_else(|| DeviceKey::Name(name.to_string()), DeviceKey::Index)
+    }
+}
+
+impl DeviceInfo {
+    /// 该设备在 [`TrafficSource::collect`] 返回的快照表中的键
+    pub fn key(&self) -> DeviceKey {
+        self.index.map_or_else(|| DeviceKey::Name(self.name.clone()), DeviceKey::Index)
+    }
+
+    /// 以 JSON 对象形式输出 (不依赖 serde)
+    pub fn to_json(&self) -> String {
+        let opt_str = |v: &Option<String>| v.as_deref().map_or("null".to_string(), json_string);
+        format!(
+            "{{\"name\":{},\"addrs\":[{}],\"addrs6\":[{}],\"mac\":{},\"type\":{},\"link_speed_mbps\":{},\"link_up\":{},\"index\":{},\"vpn\":{}}}",
+            json_string(&self.name),
+            self.addrs.iter().map(|a| json_string(a)).collect::<Vec<_>>().join(","),
+            self.addrs6.iter().map(|a| json_string(a)).collect::<Vec<_>>().join(","),
+            opt_str(&self.mac),
+            opt_str(&self.kind),
+            self.link_speed_mbps.map_or("null".to_string(), |s| s.to_string()),
+            self.link_up.map_or("null".to_string(), |u| u.to_string()),
+            self.index.map_or("null".to_string(), |i| i.to_string()),
+            self.vpn.map_or("null".to_string(), |v| v.to_string()),
+        )
+    }
+}
+
+/// 转义为 JSON 字符串字面量
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 读取接口类型与链路速率 (Linux: /sys/class/net)
+#[cfg(target_os = "linux")]
+fn link_details(name: &str) -> (Option<String>, Option<u64>) {
+    use std::path::Path;
+    let dir = Path::new("/sys/class/net").join(name);
+    let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok();
+    // ARPHRD_* 类型号，见 linux/if_arp.h
+    let kind = read("type")
+        .and_then(|t| t.trim().parse::<u32>().ok())
+        .map(|t| match t {
+            1 if dir.join("wireless").exists() => "wireless",
+            1 => "ethernet",
+            772 => "loopback",
+            768 | 769 | 776 | 778 | 65534 => "tunnel",
+            _ => "other",
+        })
+        .map(str::to_string);
+    // 链路断开时读取失败或为 -1
+    let speed = read("speed")
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .filter(|&s| s > 0)
+        .map(|s| s as u64);
+    (kind, speed)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn link_details(_name: &str) -> (Option<String>, Option<u64>) {
+    (None, None)
+}
+
+/// 读取链路状态 (Linux: /sys/class/net/<name>/operstate)
+///
+/// operstate 为 "unknown" 时 (如 lo、部分 tun 设备) 退回检查 IFF_UP 标志与 carrier。
+#[cfg(target_os = "linux")]
+fn read_link_up(name: &str) -> Option<bool> {
+    use std::path::Path;
+    let dir = Path::new("/sys/class/net").join(name);
+    let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok();
+    match read("operstate")?.trim() {
+        "up" => Some(true),
+        "down" | "lowerlayerdown" | "notpresent" | "dormant" => Some(false),
+        _ => {
+            let flags = read("flags")?;
+            let flags = u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).ok()?;
+            // IFF_UP = 0x1；管理性关闭时读取 carrier 会失败
+            let carrier = read("carrier").is_some_and(|c| c.trim() == "1");
+            Some(flags & 0x1 != 0 && carrier)
+        }
+    }
 }
 
+#[cfg(not(target_os = "linux"))]
+fn read_link_up(_name: &str) -> Option<bool> {
+    None
+}
+
+/// 读取接口索引 (Linux: /sys/class/net/<name>/ifindex)
+#[cfg(target_os = "linux")]
+fn read_ifindex(name: &str) -> Option<u32> {
+    let path = std::path::Path::new("/sys/class/net").join(name).join("ifindex");
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_ifindex(_name: &str) -> Option<u32> {
+    None
+}
+
+/// 判断是否为 VPN 隧道接口 (Linux: /sys/class/net)
+///
+/// TUN/TAP 设备带有 `tun_flags` 属性 (OpenVPN 等)，WireGuard 在 uevent 中标明 DEVTYPE。
+#[cfg(target_os = "linux")]
+fn detect_vpn(name: &str) -> Option<bool> {
+    let dir = std::path::Path::new("/sys/class/net").join(name);
+    let uevent = std::fs::read_to_string(dir.join("uevent")).ok()?;
+    let wireguard = uevent.lines().any(|l| l.trim() == "DEVTYPE=wireguard");
+    Some(wireguard || dir.join("tun_flags").exists())
+}
+
+/// 判断是否为 VPN 隧道接口 (macOS: 系统 VPN 与 WireGuard 使用 utun*，IPSec 使用 ipsec*)
+#[cfg(target_os = "macos")]
+fn detect_vpn(name: &str) -> Option<bool> {
+    Some(name.starts_with("utun") || name.starts_with("ipsec"))
+}
+
+/// 其他平台 (Windows 的 VPN 适配器名称由厂商决定) 无法可靠判断，不做标注
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn detect_vpn(_name: &str) -> Option<bool> {
+    None
+}
+
+/// 流量数据源：可替换的采集后端
+///
+/// 返回的快照以 [`DeviceInfo::key`] 为键 (有接口索引时为索引，否则为名称)，
+/// 字节数为单调递增的累计值，交给 [`crate::StatisticsEngine`] 计算速率。
+///
+/// 没有索引的同名设备无法区分各自的计数器；
+/// 使用方应先用 [`merge_duplicate_devices`] 合并这类设备。
+pub trait TrafficSource {
+    /// 枚举该数据源提供的所有设备
+    fn devices(&self) -> Vec<DeviceInfo>;
+    /// 采集一次所有设备的当前累计数据
+    fn collect(&mut self) -> HashMap<DeviceKey, Snapshot>;
+    /// 数据源异常时的提示 (如远程连接断开)，正常时为 None
+    fn status(&self) -> Option<String> {
+        None
+    }
+    /// 设备当前的链路状态 (已启用且有载波)，无法获取时为 None
+    fn link_up(&self, _name: &str) -> Option<bool> {
+        None
+    }
+    /// 重新枚举设备列表，使之后的 [`Self::devices`] 包含启动后才出现的设备 (热插拔)
+    fn refresh_devices(&mut self) {}
+}
+
+/// 合并快照键相同的设备 (接口索引相同，或都没有索引且同名)
+///
+/// 这些设备在快照表中只对应一个条目，分开显示只会得到两份相同的数据，
+/// 因此合并为一个: 保留首次出现的位置，地址取并集，其余字段取第一个非空值。
+/// 索引不同的同名设备 (如某些系统上拆分出的收 / 发伪接口) 各自保留。
+pub fn merge_duplicate_devices(devices: Vec<DeviceInfo>) -> Vec<DeviceInfo> {
+    let mut merged: Vec<DeviceInfo> = Vec::with_capacity(devices.len());
+    for dev in devices {
+        let Some(existing) = merged.iter_mut().find(|d| d.key() == dev.key()) else {
+            merged.push(dev);
+            continue;
+        };
+        for addr in dev.addrs {
+            if !existing.addrs.contains(&addr) {
+                existing.addrs.push(addr);
+            }
+        }
+        for addr in dev.addrs6 {
+            if !existing.addrs6.contains(&addr) {
+                existing.addrs6.push(addr);
+            }
+        }
+        existing.mac = existing.mac.take().or(dev.mac);
+        existing.kind = existing.kind.take().or(dev.kind);
+        existing.link_speed_mbps = existing.link_speed_mbps.or(dev.link_speed_mbps);
+        existing.link_up = existing.link_up.or(dev.link_up);
+        existing.index = existing.index.or(dev.index);
+        existing.vpn = existing.vpn.or(dev.vpn);
+    }
+    merged
+}
+
+impl TrafficSource for Collector {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        Collector::devices(self)
+    }
+
+    fn collect(&mut self) -> HashMap<DeviceKey, Snapshot> {
+        Collector::collect(self)
+    }
+
+    fn status(&self) -> Option<String> {
+        self.permission_warning()
+    }
+
+    fn link_up(&self, name: &str) -> Option<bool> {
+        read_link_up(name)
+    }
+
+    fn refresh_devices(&mut self) {
+        Collector::refresh_devices(self)
+    }
+}
+
+/// 网卡计数器的读取状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CounterAccess {
+    /// 读到过非零计数
+    Readable,
+    /// 有地址且链路未断开，但启动一段时间后计数仍全为 0 (多为权限不足)
+    Zero,
+    /// 尚无法判断 (刚启动、无地址或回环接口)
+    Unknown,
+}
+
+impl CounterAccess {
+    /// 用于 --debug-info 的说明文字
+    pub fn describe(self) -> &'static str {
+        match self {
+            CounterAccess::Readable => "readable",
+            CounterAccess::Zero => "stuck at zero (may need root/administrator)",
+            CounterAccess::Unknown => "unknown",
+        }
+    }
+}
+
+/// 启动后计数一直为 0 多久才认为读取失败
+const ZERO_COUNTER_GRACE: Duration = Duration::from_secs(5);
+
 /// 网络流量采集器
+///
+/// 每次调用 [`Collector::collect`] 返回以 [`DeviceKey`] 为键的累计计数快照，
+/// 交给 [`crate::StatisticsEngine::update`] 计算速率。
 pub struct Collector {
     networks: Networks,
     start: Instant,
+    /// 读到过非零计数的网卡
+    counted: HashSet<String>,
+}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Collector {
+    /// 创建采集器并枚举当前所有网络接口
     pub fn new() -> Self {
         Self {
             networks: Networks::new_with_refreshed_list(),
             start: Instant::now(),
+            counted: HashSet::new(),
+        }
+    }
+
+    /// 某网卡计数器的读取状态
+    ///
+    /// sysinfo 读取失败时不报错而是返回 0，所以只能看计数是否一直为 0: 有地址、
+    /// 链路未断开的网卡启动 [`ZERO_COUNTER_GRACE`] 后仍未读到任何流量，才算读取失败。
+    pub fn counter_access(&self, name: &str) -> CounterAccess {
+        if self.counted.contains(name) {
+            return CounterAccess::Readable;
+        }
+        let Some(data) = self.networks.get(name) else {
+            return CounterAccess::Unknown;
+        };
+        let ips = data.ip_networks();
+        if self.start.elapsed() < ZERO_COUNTER_GRACE
+            || ips.is_empty()
+            || ips.iter().any(|n| n.addr.is_loopback())
+            || read_link_up(name) == Some(false)
+        {
+            return CounterAccess::Unknown;
+        }
+        CounterAccess::Zero
+    }
+
+    /// 计数器一直读到 0 (多为权限不足) 时的提示
+    pub fn permission_warning(&self) -> Option<String> {
+        let zero: Vec<&str> = self
+            .networks
+            .keys()
+            .filter(|name| !self.counted.contains(*name))
+            .filter(|name| self.counter_access(name) == CounterAccess::Zero)
+            .map(String::as_str)
+            .collect();
+        match zero.as_slice() {
+            [] => None,
+            [name] => Some(format!(
+                "Counters of {name} stay at zero; run as root/administrator for full stats"
+            )),
+            names => Some(format!(
+                "Counters of {} interfaces stay at zero; run as root/administrator for full stats",
+                names.len()
+            )),
         }
     }
 
@@ -46,39 +369,65 @@ impl Collector {
 
     /// 打印所有网络接口的调试信息
     pub fn print_debug_info(&self) {
-        println!("\n=== Network Interfaces Debug Info ===");
-        println!("Total interfaces detected by sysinfo: {}\n", self.networks.len());
+        print!("{}", self.debug_info());
+    }
+
+    /// 生成调试信息文本 (`--debug-info` 与 TUI 中的 `i` 浮层共用)
+    pub fn debug_info(&self) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        let _ = writeln!(out, "\n=== Network Interfaces Debug Info ===");
+        let _ = writeln!(out, "Total interfaces detected by sysinfo: {}\n", self.networks.len());
 
         for (name, data) in self.networks.iter() {
-            println!("Interface: {}", name);
-            println!("  MAC address: {}", data.mac_address());
-            println!("  Total received: {} bytes", data.total_received());
-            println!("  Total transmitted: {} bytes", data.total_transmitted());
-            println!("  IP networks:");
-            
+            let _ = writeln!(out, "Interface: {}", name);
+            let index = read_ifindex(name).map_or("(unknown)".to_string(), |i| i.to_string());
+            let _ = writeln!(out, "  Index: {}", index);
+            let _ = writeln!(out, "  MAC address: {}", data.mac_address());
+            let _ = writeln!(out, "  Counters: {}", self.counter_access(name).describe());
+            let _ = writeln!(out, "  Total received: {} bytes", data.total_received());
+            let _ = writeln!(out, "  Total transmitted: {} bytes", data.total_transmitted());
+            let _ = writeln!(out, "  IP networks:");
+
             let ip_networks = data.ip_networks();
             if ip_networks.is_empty() {
-                println!("    (none)");
+                let _ = writeln!(out, "    (none)");
             } else {
                 for ip in ip_networks {
-                    println!("    - {} (prefix: {})", ip.addr, ip.prefix);
+                    let _ = writeln!(out, "    - {} (prefix: {})", ip.addr, ip.prefix);
                 }
             }
-            println!();
+            let _ = writeln!(out);
         }
 
-        println!("Filtered devices (IPv4 only, used in UI): {}\n", self.devices().len());
+        let _ = writeln!(out, "Filtered devices (IPv4 only, used in UI): {}\n", self.devices().len());
         for dev in self.devices() {
-            println!("  - {} [{}]", dev.name, dev.addrs.join(", "));
+            let index = dev.index.map_or("-".to_string(), |i| i.to_string());
+            let vpn = if dev.vpn == Some(true) { " (vpn)" } else { "" };
+            let _ = writeln!(out, "  - {} (index {}){} [{}]", dev.name, index, vpn, dev.addrs.join(", "));
         }
 
         // Windows loopback 说明
         #[cfg(target_os = "windows")]
         {
-            println!("\nNote: Windows loopback (127.0.0.1) traffic is not visible via");
-            println!("  standard network APIs. The Loopback device appears in the");
-            println!("  list but may show zero traffic.");
+            let _ = writeln!(out, "\nNote: Windows loopback (127.0.0.1) traffic is not visible via");
+            let _ = writeln!(out, "  standard network APIs. The Loopback device appears in the");
+            let _ = writeln!(out, "  list but may show zero traffic.");
         }
+        out
+    }
+
+    /// 所有设备信息的 JSON 数组 (每行一个设备，供脚本解析)
+    pub fn devices_json(&self) -> String {
+        let devs = self.devices();
+        let mut out = String::from("[\n");
+        for (i, dev) in devs.iter().enumerate() {
+            let sep = if i + 1 < devs.len() { "," } else { "" };
+            out.push_str(&format!("  {}{sep}\n", dev.to_json()));
+        }
+        out.push_str("]\n");
+        out
     }
 
     /// 获取所有可用设备信息（按名称排序）
@@ -93,9 +442,29 @@ impl Collector {
                     .filter(|n| n.addr.is_ipv4())
                     .map(|n| n.addr.to_string())
                     .collect();
+                let mut v6: Vec<_> = data
+                    .ip_networks()
+                    .iter()
+                    .filter_map(|n| match n.addr {
+                        std::net::IpAddr::V6(a) => Some(a),
+                        _ => None,
+                    })
+                    .collect();
+                // fe80::/10 链路本地地址排到最后
+                v6.sort_by_key(|a| (a.segments()[0] & 0xffc0) == 0xfe80);
+                let addrs6 = v6.iter().map(|a| a.to_string()).collect();
+                let mac = data.mac_address();
+                let (kind, link_speed_mbps) = link_details(name);
                 DeviceInfo {
                     name: name.to_string(),
                     addrs,
+                    addrs6,
+                    mac: (!mac.is_unspecified()).then(|| mac.to_string()),
+                    kind,
+                    link_speed_mbps,
+                    link_up: read_link_up(name),
+                    index: read_ifindex(name),
+                    vpn: detect_vpn(name),
                 }
             })
             .collect();
@@ -112,6 +481,10 @@ impl Collector {
                 devs.push(DeviceInfo {
                     name: "Loopback Pseudo-Interface 1".to_string(),
                     addrs: vec!["127.0.0.1".to_string()],
+                    kind: Some("loopback".to_string()),
+                    // Loopback Pseudo-Interface 1 的接口索引固定为 1
+                    index: Some(1),
+                    ..Default::default()
                 });
             }
         }
@@ -120,18 +493,28 @@ impl Collector {
         devs
     }
 
+    /// 重建接口列表: 加入新出现的接口、移除已消失的接口，已有接口的计数器不受影响
+    pub fn refresh_devices(&mut self) {
+        self.networks.refresh_list();
+    }
+
     /// 采集一次所有网卡的当前累计数据
-    pub fn collect(&mut self) -> HashMap<String, Snapshot> {
+    pub fn collect(&mut self) -> HashMap<DeviceKey, Snapshot> {
         // refresh() 只刷新已有接口的数据，不重建列表，计数器不会丢失
         self.networks.refresh();
         let elapsed = self.start.elapsed().as_secs_f64();
+        for (name, data) in self.networks.iter() {
+            if data.total_received() > 0 || data.total_transmitted() > 0 {
+                self.counted.insert(name.to_string());
+            }
+        }
 
         #[cfg(target_os = "windows")]
-        let mut snapshots: HashMap<String, Snapshot> = self.networks
+        let mut snapshots: HashMap<DeviceKey, Snapshot> = self.networks
             .iter()
             .map(|(name, data)| {
                 (
-                    name.to_string(),
+                    DeviceKey::local(name),
                     Snapshot {
                         elapsed_secs: elapsed,
                         bytes_recv: data.total_received(),
@@ -142,11 +525,11 @@ impl Collector {
             .collect();
         
         #[cfg(not(target_os = "windows"))]
-        let snapshots: HashMap<String, Snapshot> = self.networks
+        let snapshots: HashMap<DeviceKey, Snapshot> = self.networks
             .iter()
             .map(|(name, data)| {
                 (
-                    name.to_string(),
+                    DeviceKey::local(name),
                     Snapshot {
                         elapsed_secs: elapsed,
                         bytes_recv: data.total_received(),
@@ -159,13 +542,14 @@ impl Collector {
         // Windows 平台为 Loopback 添加快照（暂无法获取真实流量）
         #[cfg(target_os = "windows")]
         {
-            let has_loopback_snapshot = snapshots.keys().any(|k| {
+            let has_loopback_snapshot = self.networks.keys().any(|k| {
                 k.to_lowercase().contains("loopback")
             });
 
+            // 键与 devices() 中伪接口的索引 1 一致
             if !has_loopback_snapshot {
                 snapshots.insert(
-                    "Loopback Pseudo-Interface 1".to_string(),
+                    DeviceKey::Index(1),
                     Snapshot {
                         elapsed_secs: elapsed,
                         bytes_recv: 0,
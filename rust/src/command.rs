@@ -0,0 +1,126 @@
+//! 自定义命令数据源 (--source-cmd)
+//! 每次采集运行一次用户命令，从标准输出解析各设备的累计计数器。
+//!
+//! 输出格式: 每行一个设备，`<device> <rx_bytes> <tx_bytes>`，以空白分隔；
+//! 设备名可以包含空格 (取最后两列为计数器)，空行和以 `#` 开头的行会被忽略。
+//!
+//! 命令通过 `sh -c` (Windows 上为 `cmd /C`) 执行，会阻塞主循环，应尽量快速返回。
+//! 命令失败或输出格式错误时跳过这一拍，并通过 [`TrafficSource::status`] 提示原因。
+
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+use winload::collector::{DeviceInfo, DeviceKey, Snapshot, TrafficSource};
+
+/// 自定义命令数据源
+pub struct CommandSource {
+    cmd: String,
+    devices: Vec<DeviceInfo>,
+    start: Instant,
+    /// 最近一次失败的原因，成功后清空
+    last_error: Option<String>,
+}
+
+impl CommandSource {
+    /// 先运行一次命令以枚举设备；失败时返回错误说明
+    pub fn new(cmd: &str) -> Result<Self, String> {
+        let start = Instant::now();
+        let first = run(cmd, 0.0)?;
+        let mut devices: Vec<DeviceInfo> = first
+            .into_keys()
+            .map(|name| DeviceInfo {
+                name,
+                ..Default::default()
+            })
+            .collect();
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Self {
+            cmd: cmd.to_string(),
+            devices,
+            start,
+            last_error: None,
+        })
+    }
+}
+
+impl TrafficSource for CommandSource {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        self.devices.clone()
+    }
+
+    fn collect(&mut self) -> HashMap<DeviceKey, Snapshot> {
+        match run(&self.cmd, self.start.elapsed().as_secs_f64()) {
+            Ok(snapshots) => {
+                self.last_error = None;
+                snapshots.into_iter().map(|(name, s)| (DeviceKey::Name(name), s)).collect()
+            }
+            Err(e) => {
+                self.last_error = Some(e);
+                HashMap::new()
+            }
+        }
+    }
+
+    fn status(&self) -> Option<String> {
+        self.last_error
+            .as_ref()
+            .map(|e| format!("--source-cmd skipped a sample: {e}"))
+    }
+}
+
+/// 运行命令并解析输出
+fn run(cmd: &str, elapsed_secs: f64) -> Result<HashMap<String, Snapshot>, String> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", cmd]);
+        c
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut command = {
+        let mut c = Command::new("sh");
+        c.args(["-c", cmd]);
+        c
+    };
+    let output = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| format!("failed to run command: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("command exited with {}", output.status));
+    }
+    parse(&String::from_utf8_lossy(&output.stdout), elapsed_secs)
+}
+
+/// 解析 `<device> <rx_bytes> <tx_bytes>` 格式的输出，任意一行格式错误即整体失败
+fn parse(text: &str, elapsed_secs: f64) -> Result<HashMap<String, Snapshot>, String> {
+    let mut snapshots = HashMap::new();
+    for (lineno, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let malformed = || format!("malformed line {}: {line:?}", lineno + 1);
+        let mut fields = line.rsplitn(3, char::is_whitespace);
+        let (Some(tx), Some(rx), Some(name)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(malformed());
+        };
+        let (Ok(bytes_recv), Ok(bytes_sent)) = (rx.parse(), tx.parse()) else {
+            return Err(malformed());
+        };
+        snapshots.insert(
+            name.trim_end().to_string(),
+            Snapshot {
+                elapsed_secs,
+                bytes_recv,
+                bytes_sent,
+            },
+        );
+    }
+    if snapshots.is_empty() {
+        return Err("command printed no devices".to_string());
+    }
+    Ok(snapshots)
+}
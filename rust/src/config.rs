@@ -0,0 +1,205 @@
+//! 配置文件 (`config.toml`)
+//! 保存常用的命令行选项，命令行参数与环境变量优先于配置文件。
+//!
+//! 默认位置 (可用 `--config` 指定其他文件，`--no-config` 忽略):
+//! - Linux / macOS: `$XDG_CONFIG_HOME/winload/config.toml`，未设置时为 `~/.config/winload/config.toml`
+//! - Windows: `%APPDATA%\winload\config.toml`
+//!
+//! 顶层的键即长选项名 (`-` 与 `_` 等价)，`[keys]` 表为快捷键重映射 (动作 = 按键):
+//!
+//! ```toml
+//! interval = 1000
+//! average = 60
+//! device = "eth0"
+//! unicode = true
+//! in-color = "0x00d7ff"
+//! favorite = ["eth0", "wlan0"]
+//!
+//! [keys]
+//! freeze = "z"
+//! ```
+//!
+//! 只支持 TOML 的常用子集: 布尔、数字、字符串 (基本 / 字面)、单行数组、`#` 注释与 `[keys]` 表。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// 配置项的值
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// 开关选项: true 相当于在命令行给出该选项，false 相当于不给
+    Bool(bool),
+    /// 数字或字符串，原样作为选项的参数
+    Text(String),
+    /// 数组: 对可重复的选项逐个给出
+    List(Vec<String>),
+}
+
+/// 解析后的配置文件
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// 顶层选项 (长选项名, 值)，按出现顺序
+    pub options: Vec<(String, Value)>,
+    /// `[keys]` 表: (动作名, 按键)
+    pub keys: Vec<(String, String)>,
+}
+
+/// 配置目录，无法确定时返回 None
+pub fn config_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("APPDATA").map(|d| PathBuf::from(d).join("winload"))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))
+            .map(|d| d.join("winload"))
+    }
+}
+
+/// 默认配置文件路径
+pub fn default_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join(CONFIG_FILE))
+}
+
+/// 读取配置文件；`required` 为 false 时文件不存在视为没有配置
+pub fn load(path: &Path, required: bool) -> Result<Option<Config>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text)
+            .map(Some)
+            .map_err(|e| format!("{}: {e}", path.display())),
+        Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: cannot read: {e}", path.display())),
+    }
+}
+
+/// 解析配置文本，错误信息带行号
+pub fn parse(text: &str) -> Result<Config, String> {
+    let mut config = Config::default();
+    let mut in_keys = false;
+    for (i, raw) in text.lines().enumerate() {
+        let err = |msg: String| format!("line {}: {msg}", i + 1);
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            match section.trim() {
+                "keys" => in_keys = true,
+                other => return Err(err(format!("unknown table [{other}] (only [keys] is supported)"))),
+            }
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| err(format!("expected key = value, got: {line}")))?;
+        let key = unquote(key.trim()).unwrap_or_else(|| key.trim().to_string());
+        if key.is_empty() {
+            return Err(err("missing key".to_string()));
+        }
+        let value = parse_value(value.trim()).map_err(err)?;
+        if in_keys {
+            match value {
+                Value::Text(k) => config.keys.push((key.replace('_', "-"), k)),
+                _ => return Err(err(format!("key for '{key}' must be a string, e.g. \"z\""))),
+            }
+        } else {
+            config.options.push((key.replace('_', "-"), value));
+        }
+    }
+    Ok(config)
+}
+
+/// 去掉字符串之外的 `#` 注释
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+/// 解析带引号的字符串: "基本字符串" (支持常用转义) 或 '字面字符串'，不是字符串时返回 None
+fn unquote(s: &str) -> Option<String> {
+    if let Some(inner) = s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        return Some(inner.to_string());
+    }
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(c @ ('"' | '\\')) => out.push(c),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+fn parse_value(s: &str) -> Result<Value, String> {
+    match s {
+        "true" => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+    if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        return split_array(inner)
+            .into_iter()
+            .map(|item| match parse_value(item)? {
+                Value::Text(t) => Ok(t),
+                _ => Err(format!("arrays may only hold strings and numbers: {s}")),
+            })
+            .collect::<Result<_, _>>()
+            .map(Value::List);
+    }
+    if let Some(text) = unquote(s) {
+        return Ok(Value::Text(text));
+    }
+    // 数字原样交给对应选项解析，允许 TOML 的 1_000 写法
+    if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit() || "+-._eE".contains(c)) {
+        return Ok(Value::Text(s.replace('_', "")));
+    }
+    Err(format!("invalid value: {s} (strings need quotes)"))
+}
+
+/// 按顶层逗号拆分数组内容 (引号内的逗号不拆)，忽略末尾多余的逗号
+fn split_array(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ',' => {
+                items.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            None => {}
+        }
+    }
+    items.push(inner[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
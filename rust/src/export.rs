@@ -0,0 +1,76 @@
+//! 导出统计数据: 单个网卡的速率历史 (e 键) 与退出时的 JSON 报告 (--report-json)
+//!
+//! e 键把当前保留的全部历史 (收发两个方向) 立即写成 CSV，用于在看到异常后抓取当时屏幕上的那段数据。
+//! 文件写在当前目录，名为 `winload-<网卡>-<unix 秒>.csv`，按时间从旧到新:
+//!
+//! ```text
+//! unix_time,elapsed_secs,incoming_bytes_per_sec,outgoing_bytes_per_sec
+//! 1760600000.250,0.000,1520.0,384.0
+//! ```
+//!
+//! `elapsed_secs` 相对本次运行的第一个样本 (见 [`StatisticsEngine::incoming_timed`])，
+//! 从上次运行恢复的样本为负值；`unix_time` 由它与导出时刻换算得到。
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use winload::collector::json_string;
+use winload::stats::StatisticsEngine;
+
+const HEADER: &str = "unix_time,elapsed_secs,incoming_bytes_per_sec,outgoing_bytes_per_sec";
+
+/// 生成速率历史的 CSV 文本；`now_unix` 为最新样本对应的 Unix 时间 (秒)
+pub fn history_csv(engine: &StatisticsEngine, now_unix: f64) -> String {
+    let latest = engine.incoming_timed().next().map_or(0.0, |(t, _)| t);
+    let mut out = String::from(HEADER);
+    out.push('\n');
+    for ((t, recv), (_, sent)) in engine.incoming_timed().rev().zip(engine.outgoing_timed().rev()) {
+        let unix = now_unix - (latest - t);
+        let _ = writeln!(out, "{unix:.3},{t:.3},{recv:.1},{sent:.1}");
+    }
+    out
+}
+
+/// 把 `name` 网卡的速率历史写入当前目录下带时间戳的 CSV 文件，返回文件路径
+pub fn export_history(name: &str, engine: &StatisticsEngine) -> io::Result<PathBuf> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64());
+    // 网卡名可能含空格、斜杠等 (如 Windows 的适配器描述)，文件名中替换为下划线
+    let safe: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    let path = PathBuf::from(format!("winload-{safe}-{}.csv", now as u64));
+    std::fs::write(&path, history_csv(engine, now))?;
+    Ok(path)
+}
+
+/// 退出报告: 所有网卡一个 JSON 对象，速率单位为 bytes/s，字段名保持稳定供脚本断言
+///
+/// ```text
+/// {"duration_secs":60.0,"devices":[{"name":"eth0",
+///   "in":{"current":..,"average":..,"minimum":..,"maximum":..,"total":..},"out":{..},
+///   "session_bytes":{"in":..,"out":..}}]}
+/// ```
+///
+/// `total` 为计数器的累计值 (同 Ttl)，`session_bytes` 为本次运行期间传输的字节数。
+pub fn report_json<'a>(
+    devices: impl Iterator<Item = (&'a str, &'a StatisticsEngine)>,
+    duration_secs: f64,
+) -> String {
+    let devices: Vec<String> = devices
+        .map(|(name, engine)| {
+            let (session_in, session_out) = engine.session_totals();
+            format!(
+                "{{\"name\":{},\"in\":{},\"out\":{},\"session_bytes\":{{\"in\":{session_in},\"out\":{session_out}}}}}",
+                json_string(name),
+                engine.incoming.to_json(),
+                engine.outgoing.to_json(),
+            )
+        })
+        .collect();
+    format!("{{\"duration_secs\":{duration_secs:.1},\"devices\":[{}]}}\n", devices.join(","))
+}
@@ -0,0 +1,302 @@
+//! 抓包过滤表达式 (--filter)
+//! 只统计匹配的 IP 包，把抓包后端变成针对某台主机 / 某个网段的流量监视器。
+//!
+//! 支持 BPF (tcpdump) 语法的常用子集，Linux 与 Windows 行为一致:
+//!
+//! - `host ADDR`、`net ADDR/PREFIX`、`port N`、`portrange N-M`，
+//!   可加方向限定 `src` / `dst` (默认任一方向)
+//! - 协议: `tcp`、`udp`、`icmp`、`icmp6`、`ip`、`ip6`
+//! - 组合: `and` / `&&`、`or` / `||`、`not` / `!`、括号
+//!
+//! 例: `host 10.0.0.5 and not port 22`、`net 192.168.1.0/24 or dst port 443`。
+//! 地址只接受 IP 字面量 (不做 DNS 解析)；表达式错误时 [`PacketFilter::parse`] 返回说明。
+
+use std::net::IpAddr;
+
+/// 编译后的过滤表达式
+#[derive(Clone, Debug)]
+pub struct PacketFilter {
+    expr: Expr,
+}
+
+/// 方向限定
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Dir {
+    Src,
+    Dst,
+    Any,
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Host(Dir, IpAddr),
+    Net(Dir, IpAddr, u8),
+    Port(Dir, u16, u16),
+    /// IP 协议号
+    Proto(u8),
+    Ipv4,
+    Ipv6,
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+/// 过滤所需的 IP 包字段
+struct Packet {
+    src: IpAddr,
+    dst: IpAddr,
+    proto: u8,
+    /// TCP / UDP 的 (源端口, 目的端口)，分片或其他协议为 None
+    ports: Option<(u16, u16)>,
+}
+
+impl PacketFilter {
+    /// 解析过滤表达式
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let tokens = tokenize(s);
+        if tokens.is_empty() {
+            return Err("empty filter expression".to_string());
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if let Some(tok) = parser.peek() {
+            return Err(format!("unexpected '{tok}' in filter expression"));
+        }
+        Ok(Self { expr })
+    }
+
+    /// `ip` 为从 IP 头开始的包数据 (可以被截断，只需包含 IP 头与端口)
+    pub fn matches(&self, ip: &[u8]) -> bool {
+        parse_packet(ip).is_some_and(|p| eval(&self.expr, &p))
+    }
+}
+
+fn eval(expr: &Expr, p: &Packet) -> bool {
+    let either = |dir: Dir, f: &dyn Fn(IpAddr) -> bool| match dir {
+        Dir::Src => f(p.src),
+        Dir::Dst => f(p.dst),
+        Dir::Any => f(p.src) || f(p.dst),
+    };
+    match expr {
+        Expr::Host(dir, addr) => either(*dir, &|a| a == *addr),
+        Expr::Net(dir, net, prefix) => either(*dir, &|a| in_net(a, *net, *prefix)),
+        Expr::Port(dir, lo, hi) => p.ports.is_some_and(|(sp, dp)| {
+            let hit = |port: u16| (*lo..=*hi).contains(&port);
+            match dir {
+                Dir::Src => hit(sp),
+                Dir::Dst => hit(dp),
+                Dir::Any => hit(sp) || hit(dp),
+            }
+        }),
+        Expr::Proto(n) => p.proto == *n,
+        Expr::Ipv4 => p.src.is_ipv4(),
+        Expr::Ipv6 => p.src.is_ipv6(),
+        Expr::Not(e) => !eval(e, p),
+        Expr::And(a, b) => eval(a, p) && eval(b, p),
+        Expr::Or(a, b) => eval(a, p) || eval(b, p),
+    }
+}
+
+/// 地址是否在 `net/prefix` 内 (地址族不同视为不匹配)
+fn in_net(addr: IpAddr, net: IpAddr, prefix: u8) -> bool {
+    match (addr, net) {
+        (IpAddr::V4(a), IpAddr::V4(n)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(a) & mask == u32::from(n) & mask
+        }
+        (IpAddr::V6(a), IpAddr::V6(n)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(a) & mask == u128::from(n) & mask
+        }
+        _ => false,
+    }
+}
+
+fn parse_packet(data: &[u8]) -> Option<Packet> {
+    let port_pair = |proto: u8, l4: Option<&[u8]>| {
+        let l4 = l4.filter(|_| proto == 6 || proto == 17)?;
+        let sp = u16::from_be_bytes(l4.get(0..2)?.try_into().ok()?);
+        let dp = u16::from_be_bytes(l4.get(2..4)?.try_into().ok()?);
+        Some((sp, dp))
+    };
+    match data.first()? >> 4 {
+        4 if data.len() >= 20 => {
+            let ihl = (data[0] & 0x0f) as usize * 4;
+            let src: [u8; 4] = data[12..16].try_into().ok()?;
+            let dst: [u8; 4] = data[16..20].try_into().ok()?;
+            // 非首个分片不含传输层头
+            let frag_offset = u16::from_be_bytes([data[6], data[7]]) & 0x1fff;
+            let l4 = data.get(ihl..).filter(|_| frag_offset == 0);
+            Some(Packet {
+                src: IpAddr::from(src),
+                dst: IpAddr::from(dst),
+                proto: data[9],
+                ports: port_pair(data[9], l4),
+            })
+        }
+        6 if data.len() >= 40 => {
+            let src: [u8; 16] = data[8..24].try_into().ok()?;
+            let dst: [u8; 16] = data[24..40].try_into().ok()?;
+            // 只看固定头的下一个头部，带扩展头的包没有端口信息
+            Some(Packet {
+                src: IpAddr::from(src),
+                dst: IpAddr::from(dst),
+                proto: data[6],
+                ports: port_pair(data[6], data.get(40..)),
+            })
+        }
+        _ => None,
+    }
+}
+
+// ─── 解析 ──────────────────────────────────────────────────
+
+fn tokenize(s: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let single = match c {
+            '(' | ')' => Some(c.to_string()),
+            '!' => Some("not".to_string()),
+            '&' | '|' if chars.peek() == Some(&c) => {
+                chars.next();
+                Some(if c == '&' { "and" } else { "or" }.to_string())
+            }
+            _ => None,
+        };
+        if c.is_whitespace() || single.is_some() {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            tokens.extend(single);
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_and()?;
+        while self.peek().is_some_and(|t| t.eq_ignore_ascii_case("or")) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_unary()?;
+        while self.peek().is_some_and(|t| t.eq_ignore_ascii_case("and")) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.parse_unary()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        let tok = self
+            .next()
+            .ok_or_else(|| "filter expression ends unexpectedly".to_string())?;
+        match tok.to_lowercase().as_str() {
+            "not" => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            "(" => {
+                let inner = self.parse_or()?;
+                match self.next().as_deref() {
+                    Some(")") => Ok(inner),
+                    _ => Err("missing ')' in filter expression".to_string()),
+                }
+            }
+            "src" => self.parse_qualified(Dir::Src),
+            "dst" => self.parse_qualified(Dir::Dst),
+            "tcp" => Ok(Expr::Proto(6)),
+            "udp" => Ok(Expr::Proto(17)),
+            "icmp" => Ok(Expr::Proto(1)),
+            "icmp6" => Ok(Expr::Proto(58)),
+            "ip" => Ok(Expr::Ipv4),
+            "ip6" => Ok(Expr::Ipv6),
+            _ => {
+                self.pos -= 1;
+                self.parse_qualified(Dir::Any)
+            }
+        }
+    }
+
+    /// 方向限定之后的 host / net / port / portrange
+    fn parse_qualified(&mut self, dir: Dir) -> Result<Expr, String> {
+        let kind = self
+            .next()
+            .ok_or_else(|| "filter expression ends unexpectedly".to_string())?;
+        let kind_lower = kind.to_lowercase();
+        if !["host", "net", "port", "portrange"].contains(&kind_lower.as_str()) {
+            return Err(format!(
+                "unknown filter primitive '{kind}' (supported: host, net, port, portrange, tcp, udp, icmp, icmp6, ip, ip6)"
+            ));
+        }
+        let value = self
+            .next()
+            .ok_or_else(|| format!("'{kind}' needs a value"))?;
+        match kind_lower.as_str() {
+            "host" => Ok(Expr::Host(dir, parse_addr(&value)?)),
+            "net" => {
+                let (addr, prefix) = match value.split_once('/') {
+                    Some((addr, prefix)) => (parse_addr(addr)?, Some(prefix)),
+                    None => (parse_addr(&value)?, None),
+                };
+                let max = if addr.is_ipv4() { 32 } else { 128 };
+                let prefix = match prefix {
+                    Some(p) => p
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|&p| p <= max)
+                        .ok_or_else(|| format!("invalid prefix length in '{value}'"))?,
+                    None => max,
+                };
+                Ok(Expr::Net(dir, addr, prefix))
+            }
+            "port" => {
+                let port = parse_port(&value)?;
+                Ok(Expr::Port(dir, port, port))
+            }
+            _ => {
+                let (lo, hi) = value
+                    .split_once('-')
+                    .ok_or_else(|| format!("portrange expects N-M, got '{value}'"))?;
+                let (lo, hi) = (parse_port(lo)?, parse_port(hi)?);
+                if lo > hi {
+                    return Err(format!("empty port range '{value}'"));
+                }
+                Ok(Expr::Port(dir, lo, hi))
+            }
+        }
+    }
+}
+
+fn parse_addr(s: &str) -> Result<IpAddr, String> {
+    s.parse()
+        .map_err(|_| format!("'{s}' is not an IP address (host names are not resolved)"))
+}
+
+fn parse_port(s: &str) -> Result<u16, String> {
+    s.parse().map_err(|_| format!("'{s}' is not a port number"))
+}
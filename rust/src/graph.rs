@@ -2,15 +2,174 @@
 //! 仿 nload 的柱状图效果
 //! - ASCII 模式 (默认): 使用 4 级字符: ' ', '.', '|', '#'
 //! - Unicode 模式 (-U): 使用 Unicode block 字符: ' ', '·', '░', '▓', '█'
+//! - 八分块模式 (`--graph-chars eighths`): 顶格按 1/8 精度使用 '▁' .. '█'，纵向分辨率提高 8 倍
+//! - 也可通过 `--graph-chars` 选择预设或自定义字符集，见 [`GraphChars`]
+//! - `--downsample` 时把全部历史压缩到图形宽度，每列合并多个样本，见 [`Downsample`]
 
 use std::collections::VecDeque;
 
-/// 返回 >= value 的最近的 2 的幂次方，最小 2048 (2 KiB/s)
+/// 图形字符集: 按每格填充程度从高到低
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GraphChars {
+    /// 填满整格
+    pub full: char,
+    /// 填充 >= 70%
+    pub high: char,
+    /// 填充 >= 30%
+    pub low: char,
+    /// 填充 < 30%
+    pub dot: char,
+    /// 空白格
+    pub empty: char,
+    /// 顶格按填充比例使用八分块字符 [`EIGHTH_BLOCKS`] (此时 high / low / dot 不参与绘制)
+    pub eighths: bool,
+}
+
+/// 八分块字符: 下标 i 表示填充 (i + 1) / 8 格
+pub const EIGHTH_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+impl GraphChars {
+    /// ASCII 预设 (默认): `#` `|` `.`
+    pub const ASCII: Self = Self { full: '#', high: '|', low: '.', dot: '.', empty: ' ', eighths: false };
+    /// Unicode block 预设 (-U): `█` `▓` `░` `·`
+    pub const BLOCK: Self = Self { full: '█', high: '▓', low: '░', dot: '·', empty: ' ', eighths: false };
+    /// 阴影预设: `█` `▓` `▒` `░`，按浓度表示填充程度
+    pub const SHADE: Self = Self { full: '█', high: '▓', low: '▒', dot: '░', empty: ' ', eighths: false };
+    /// 八分块预设: 顶格按高度使用 `▁` .. `█`，柱顶平滑
+    pub const EIGHTHS: Self = Self { full: '█', high: '▆', low: '▄', dot: '▁', empty: ' ', eighths: true };
+
+    /// 解析 `--graph-chars`: 预设名 (`ascii` / `block` / `shade` / `eighths`)
+    /// 或逗号分隔的 `full,partial,empty` 三个字符 (如 `=,-, `)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "ascii" => return Ok(Self::ASCII),
+            "block" | "unicode" => return Ok(Self::BLOCK),
+            "shade" => return Ok(Self::SHADE),
+            "eighths" | "smooth" => return Ok(Self::EIGHTHS),
+            _ => {}
+        }
+        let parts: Vec<&str> = s.split(',').collect();
+        if parts.len() != 3 {
+            return Err(format!(
+                "expected a preset (ascii, block, shade, eighths) or 'full,partial,empty', got '{s}'"
+            ));
+        }
+        let chars: Vec<char> = parts
+            .iter()
+            .map(|part| {
+                let mut it = part.chars();
+                match (it.next(), it.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(format!("'{part}' is not a single character")),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        let [full, partial, empty] = chars[..] else {
+            unreachable!("checked above");
+        };
+        Ok(Self {
+            full,
+            high: partial,
+            low: partial,
+            dot: partial,
+            empty,
+            eighths: false,
+        })
+    }
+
+    /// 是否为高填充字符 (full / high)，用于着色: 高填充用主色，低填充用暗色
+    ///
+    /// 八分块是实心的部分高度字符，一律用主色。
+    pub fn is_strong(&self, ch: char) -> bool {
+        ch == self.full || ch == self.high || (self.eighths && EIGHTH_BLOCKS.contains(&ch))
+    }
+}
+
+/// 平滑绘图用的速率序列 (--display-smooth)，只影响图形，不影响统计数值
+///
+/// 以每个点为中心做三角加权平均: 距离 k 的点权重为 `radius + 1 - k`，
+/// 两端窗口不完整时按实际参与的权重归一化，因此序列长度与总体水平保持不变。
+/// `radius` 为 0 时原样返回。
+pub fn smooth_history(history: &VecDeque<f64>, radius: usize) -> VecDeque<f64> {
+    if radius == 0 {
+        return history.clone();
+    }
+    let len = history.len();
+    (0..len)
+        .map(|i| {
+            let lo = i.saturating_sub(radius);
+            let hi = (i + radius).min(len - 1);
+            let (sum, weights) = (lo..=hi).fold((0.0, 0.0), |(sum, weights), j| {
+                let w = (radius + 1 - i.abs_diff(j)) as f64;
+                (sum + history[j] * w, weights + w)
+            });
+            sum / weights
+        })
+        .collect()
+}
+
+/// 历史压缩到图形宽度时，每列合并样本的方式 (--downsample)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Downsample {
+    /// 取列内最大值，保留尖峰
+    Max,
+    /// 取列内平均值，显示平滑趋势
+    Avg,
+    /// 取列内最新的样本
+    Last,
+}
+
+/// 把最近 `samples` 个样本 (front = 最新值) 铺满 `width` 列，返回每列的值 (--downsample / 缩放)
+///
+/// 第 c 列 (0 = 最新) 覆盖样本 `[c * samples / width, (c + 1) * samples / width)`:
+/// 样本数多于列数时每列按 `mode` 合并多个样本；少于列数时一个样本拉伸为多列。
+/// 超出历史长度的部分 (刚启动时) 记为 0。
+pub fn window_history(history: &VecDeque<f64>, samples: usize, width: usize, mode: Downsample) -> VecDeque<f64> {
+    let len = history.len();
+    (0..width)
+        .map(|col| {
+            let lo = window_first_sample(samples, width, col);
+            let hi = window_first_sample(samples, width, col + 1).max(lo + 1);
+            let bucket = history.range(lo.min(len)..hi.min(len));
+            if bucket.len() == 0 {
+                return 0.0;
+            }
+            match mode {
+                Downsample::Max => bucket.copied().fold(0.0, f64::max),
+                Downsample::Avg => {
+                    let n = bucket.len();
+                    bucket.sum::<f64>() / n as f64
+                }
+                Downsample::Last => bucket.copied().next().unwrap_or(0.0),
+            }
+        })
+        .collect()
+}
+
+/// [`window_history`] 中第 `col` 列 (0 = 最新) 最新样本在原历史中的下标
+pub fn window_first_sample(samples: usize, width: usize, col: usize) -> usize {
+    (col * samples).checked_div(width).unwrap_or(col)
+}
+
+/// 自动刻度的下限 (2 KiB/s)，空闲网卡的图形不会因刻度过小而被噪声填满
+pub const MIN_SCALE: f64 = 2048.0;
+
+/// 自动刻度的上限: f64 能表示的最大 2 的幂 (2^1023)
+pub const MAX_SCALE: f64 = f64::from_bits(0x7fe0_0000_0000_0000);
+
+/// 自动刻度: 返回 >= value 的最小的 2 的幂，结果总在 [`MIN_SCALE`, `MAX_SCALE`] 内
+///
+/// - 0、负数与 NaN 返回 [`MIN_SCALE`]，保证刻度可以安全地作除数
+/// - 超过 `MAX_SCALE` 的值 (含无穷大) 返回 `MAX_SCALE`，不会溢出为无穷大
+/// - 恰好是 2 的幂的值原样返回
 pub fn next_power_of_2_scaled(value: f64) -> f64 {
-    if value <= 2048.0 {
-        return 2048.0;
+    if value.is_nan() || value <= MIN_SCALE {
+        return MIN_SCALE;
+    }
+    if value >= MAX_SCALE {
+        return MAX_SCALE;
     }
-    let mut result = 2048.0;
+    let mut result = MIN_SCALE;
     while result < value {
         result *= 2.0;
     }
@@ -22,8 +181,10 @@ pub fn next_power_of_2_scaled(value: f64) -> f64 {
 /// - `history`: 速率历史 (front = 最新值，越往后越旧)
 /// - `width`:   图形宽度（字符列数）
 /// - `height`:  图形高度（字符行数）
-/// - `max_value`: 缩放上限，0.0 表示自动
-/// - `unicode`:  true 使用 Unicode block 字符，false 使用 ASCII 字符
+/// - `max_value`: 缩放上限，0.0 表示自动 (非有限值也按自动处理)
+/// - `chars`:    图形字符集
+/// - `noise_floor`: 噪声基线 / 裁剪下限 (bytes/s)，绘制前从每个值中减去 (最低为 0)，0.0 表示不处理；
+///   与固定的 `max_value` 一起即为纵轴裁剪: 低于下限的值留空，高于上限的值顶格
 ///
 /// 返回 `height` 行的字符串列表，每行 `width` 个字符
 pub fn render_graph(
@@ -31,7 +192,8 @@ pub fn render_graph(
     width: usize,
     height: usize,
     max_value: f64,
-    unicode: bool,
+    chars: &GraphChars,
+    noise_floor: f64,
 ) -> Vec<String> {
     if width == 0 || height == 0 {
         return vec![];
@@ -42,27 +204,28 @@ pub fn render_graph(
         .iter()
         .take(width)
         .copied()
-        .map(|v| v.max(0.0))
+        .map(|v| (v - noise_floor).max(0.0))
         .collect();
 
     // 补齐不足 width 的部分
     values.resize(width, 0.0);
 
-    // 自动缩放
-    let max_val = if max_value <= 0.0 {
+    // 自动缩放 (刻度用作除数，保证为正的有限值)
+    let max_val = if max_value > 0.0 && max_value.is_finite() {
+        max_value
+    } else {
         let peak = values.iter().cloned().fold(0.0_f64, f64::max);
         next_power_of_2_scaled(peak)
-    } else {
-        max_value
     };
-    let max_val = if max_val <= 0.0 { 2048.0 } else { max_val };
 
-    // 字符集: (full, high, low, dot)
-    let (ch_full, ch_high, ch_low, ch_dot) = if unicode {
-        ('█', '▓', '░', '·')
-    } else {
-        ('#', '|', '.', '.')
-    };
+    let GraphChars {
+        full: ch_full,
+        high: ch_high,
+        low: ch_low,
+        dot: ch_dot,
+        empty: ch_empty,
+        eighths,
+    } = *chars;
 
     // 逐行渲染 (第 0 行 = 最顶部)
     let mut lines = Vec::with_capacity(height);
@@ -77,11 +240,15 @@ pub fn render_graph(
             let traffic_per_line = max_val / height as f64;
 
             if value <= lower_limit {
-                chars.push(' ');
+                chars.push(ch_empty);
             } else {
                 let rest = value - lower_limit;
                 if rest >= traffic_per_line {
                     chars.push(ch_full);
+                } else if eighths {
+                    // 顶格: 按剩余比例取最接近的八分块，非零值至少画 1/8
+                    let eighth = (rest / traffic_per_line * 8.0).round() as usize;
+                    chars.push(EIGHTH_BLOCKS[eighth.clamp(1, 8) - 1]);
                 } else if rest >= traffic_per_line * 0.7 {
                     chars.push(ch_high);
                 } else if rest >= traffic_per_line * 0.3 {
@@ -97,7 +264,106 @@ pub fn render_graph(
 }
 
 /// 返回带单位选择的图形缩放标签
-pub fn get_graph_scale_label_unit(max_value: f64, unit: crate::Unit) -> String {
+pub fn get_graph_scale_label_unit(max_value: f64, unit: crate::stats::Unit) -> String {
     use crate::stats::format_speed_unit;
     format!("100% @ {}", format_speed_unit(max_value, unit))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 把 `values` (从旧到新) 转成 render_graph 需要的历史 (front = 最新值)
+    fn history(values: &[f64]) -> VecDeque<f64> {
+        values.iter().rev().copied().collect()
+    }
+
+    #[test]
+    fn noise_floor_blanks_background_traffic() {
+        // 左列 300 B/s，右列 (最新) 50 B/s；噪声基线 100 B/s
+        let hist = history(&[300.0, 50.0]);
+        let lines = render_graph(&hist, 2, 2, 200.0, &GraphChars::ASCII, 100.0);
+        assert_eq!(lines, ["# ", "# "]);
+
+        // 不设基线时同样的 50 B/s 会画出来
+        let lines = render_graph(&hist, 2, 2, 200.0, &GraphChars::ASCII, 0.0);
+        assert_ne!(lines[1].chars().nth(1), Some(' '));
+    }
+
+    #[test]
+    fn smoothing_flattens_sawtooth() {
+        let saw: VecDeque<f64> = [0.0, 4.0, 0.0, 4.0, 0.0].into_iter().collect();
+        // radius 1: 权重 1-2-1，两端按实际权重 (2 + 1) 归一化
+        let smoothed: Vec<f64> = smooth_history(&saw, 1).into_iter().collect();
+        assert_eq!(smoothed, [4.0 / 3.0, 2.0, 2.0, 2.0, 4.0 / 3.0]);
+        // radius 0 原样返回
+        assert_eq!(smooth_history(&saw, 0), saw);
+    }
+
+    #[test]
+    fn downsample_modes() {
+        // front = 最新值；6 个样本压缩到 3 列，每列 2 个样本
+        let hist: VecDeque<f64> = [1.0, 5.0, 2.0, 2.0, 8.0, 0.0].into_iter().collect();
+        let cols = |mode| -> Vec<f64> { window_history(&hist, 6, 3, mode).into_iter().collect() };
+        assert_eq!(cols(Downsample::Max), [5.0, 2.0, 8.0]);
+        assert_eq!(cols(Downsample::Avg), [3.0, 2.0, 4.0]);
+        assert_eq!(cols(Downsample::Last), [1.0, 2.0, 8.0]);
+    }
+
+    #[test]
+    fn downsample_stretches_and_pads_short_history() {
+        let hist: VecDeque<f64> = [7.0, 3.0].into_iter().collect();
+        // 样本少于列数时一个样本拉伸为多列
+        let stretched: Vec<f64> = window_history(&hist, 2, 4, Downsample::Avg).into_iter().collect();
+        assert_eq!(stretched, [7.0, 7.0, 3.0, 3.0]);
+        // 超出历史长度的部分记为 0
+        let padded: Vec<f64> = window_history(&hist, 4, 4, Downsample::Max).into_iter().collect();
+        assert_eq!(padded, [7.0, 3.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn scale_of_degenerate_values_is_min_scale() {
+        assert_eq!(next_power_of_2_scaled(0.0), MIN_SCALE);
+        assert_eq!(next_power_of_2_scaled(-1.0), MIN_SCALE);
+        assert_eq!(next_power_of_2_scaled(f64::NEG_INFINITY), MIN_SCALE);
+        assert_eq!(next_power_of_2_scaled(f64::NAN), MIN_SCALE);
+        assert_eq!(next_power_of_2_scaled(MIN_SCALE / 2.0), MIN_SCALE);
+        assert_eq!(next_power_of_2_scaled(1.0), MIN_SCALE);
+    }
+
+    #[test]
+    fn scale_keeps_exact_powers_of_two() {
+        assert_eq!(next_power_of_2_scaled(2048.0), 2048.0);
+        assert_eq!(next_power_of_2_scaled(4096.0), 4096.0);
+        assert_eq!(next_power_of_2_scaled(1024.0 * 1024.0), 1024.0 * 1024.0);
+    }
+
+    #[test]
+    fn scale_rounds_up_just_above_a_power_of_two() {
+        assert_eq!(next_power_of_2_scaled(2049.0), 4096.0);
+        assert_eq!(next_power_of_2_scaled(1024.0 * 1024.0 + 0.5), 2.0 * 1024.0 * 1024.0);
+    }
+
+    #[test]
+    fn scale_is_capped_at_max_scale() {
+        assert_eq!(next_power_of_2_scaled(MAX_SCALE), MAX_SCALE);
+        assert_eq!(next_power_of_2_scaled(f64::MAX), MAX_SCALE);
+        assert_eq!(next_power_of_2_scaled(f64::INFINITY), MAX_SCALE);
+        assert!(MAX_SCALE.is_finite());
+    }
+
+    #[test]
+    fn crop_band_blanks_below_floor_and_fills_above_ceiling() {
+        // --crop-min 100 --crop-max 1000: 下限作为基线，刻度为两者之差
+        let (floor, ceiling) = (100.0, 1000.0);
+        let hist = history(&[50.0, 550.0, 5000.0]);
+        let lines = render_graph(&hist, 3, 4, ceiling - floor, &GraphChars::ASCII, floor);
+        let column = |col: usize| -> String { lines.iter().map(|l| l.chars().nth(col).unwrap()).collect() };
+        // 低于下限: 整列留空
+        assert_eq!(column(0), "    ");
+        // 上下限之间: 按比例绘制 (450 / 900 = 半高)
+        assert_eq!(column(1), "  ##");
+        // 高于上限: 整列顶格
+        assert_eq!(column(2), "####");
+    }
+}
@@ -0,0 +1,162 @@
+//! 混合数据源 (--device-source)
+//! 大多数网卡沿用主数据源 (系统计数器)，个别网卡改由其他后端采集，再按设备的快照键合并快照。
+//!
+//! 目前可选的后端:
+//! - `os`:   主数据源 (默认)，可用于把某块网卡排除在更宽泛的匹配之外
+//! - `pcap`: 在该网卡上抓包累计字节数 (同 --pcap 的后端，需要 root / 管理员权限)
+//!
+//! 合并规则: 该网卡以指定的后端为准，不会混用两套计数器 (否则累计值跳变会算出尖峰)；
+//! 后端启动失败时该网卡退回系统计数器，原因通过 [`TrafficSource::status`] 提示。
+
+use std::collections::HashMap;
+
+use winload::collector::{DeviceInfo, DeviceKey, Snapshot, TrafficSource};
+use crate::filter::PacketFilter;
+use crate::proto::{self, ProtoSource};
+
+/// 单网卡可选的采集后端
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// 主数据源的系统计数器
+    Os,
+    /// 抓包统计
+    Pcap,
+}
+
+impl Backend {
+    /// 命令行中的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Os => "os",
+            Backend::Pcap => "pcap",
+        }
+    }
+}
+
+/// 解析 `--device-source`，格式 "NAME=BACKEND"，如 "eth1=pcap"
+pub fn parse_device_source(s: &str) -> Result<(String, Backend), String> {
+    let (name, backend) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected NAME=BACKEND (e.g. eth1=pcap), got: {s}"))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("missing device name in: {s}"));
+    }
+    let backend = match backend.trim().to_lowercase().as_str() {
+        "os" => Backend::Os,
+        "pcap" | "npcap" => Backend::Pcap,
+        other => return Err(format!("unknown backend '{other}' (expected os or pcap)")),
+    };
+    Ok((name.to_string(), backend))
+}
+
+/// 改由抓包采集的网卡
+struct Override {
+    /// 该网卡在主数据源快照表中的键
+    device: DeviceKey,
+    source: ProtoSource,
+}
+
+/// 混合数据源: 主数据源 + 按网卡指定的其他后端
+pub struct HybridSource {
+    primary: Box<dyn TrafficSource>,
+    overrides: Vec<Override>,
+    /// 启动失败 / 未匹配到网卡的说明，持续显示在头部
+    warnings: Vec<String>,
+}
+
+impl HybridSource {
+    /// 按 `assignments` (名称模糊匹配，取第一个匹配项) 为各网卡启动后端
+    ///
+    /// `filter` 作用于所有抓包后端 (--filter)。
+    pub fn new(
+        primary: Box<dyn TrafficSource>,
+        assignments: &[(String, Backend)],
+        filter: Option<&PacketFilter>,
+    ) -> Self {
+        let devices = primary.devices();
+        let mut overrides = Vec::new();
+        let mut warnings = Vec::new();
+
+        for (pattern, _) in assignments {
+            let lower = pattern.to_lowercase();
+            if !devices.iter().any(|d| d.name.to_lowercase().contains(&lower)) {
+                warnings.push(format!("--device-source: no device matches '{pattern}'"));
+            }
+        }
+
+        for info in &devices {
+            let lower = info.name.to_lowercase();
+            let Some(&(_, backend)) = assignments
+                .iter()
+                .find(|(pat, _)| lower.contains(&pat.to_lowercase()))
+            else {
+                continue;
+            };
+            if backend == Backend::Pcap {
+                match proto::start(info, filter.cloned()) {
+                    Ok(source) => overrides.push(Override {
+                        device: info.key(),
+                        source,
+                    }),
+                    Err(e) => {
+                        // 只取第一行，完整的权限说明见 --pcap
+                        let reason = e.lines().next().unwrap_or_default();
+                        warnings.push(format!("{}: pcap unavailable ({reason}), using OS counters", info.name));
+                    }
+                }
+            }
+        }
+
+        Self {
+            primary,
+            overrides,
+            warnings,
+        }
+    }
+}
+
+impl TrafficSource for HybridSource {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        self.primary.devices()
+    }
+
+    fn collect(&mut self) -> HashMap<DeviceKey, Snapshot> {
+        let mut snapshots = self.primary.collect();
+        for o in &mut self.overrides {
+            // 抓包后端按协议拆分，合计为该网卡的一个快照，替换系统计数器
+            let parts = o.source.collect();
+            let Some(elapsed_secs) = parts.values().map(|s| s.elapsed_secs).reduce(f64::max) else {
+                snapshots.remove(&o.device);
+                continue;
+            };
+            snapshots.insert(
+                o.device.clone(),
+                Snapshot {
+                    elapsed_secs,
+                    bytes_recv: parts.values().map(|s| s.bytes_recv).sum(),
+                    bytes_sent: parts.values().map(|s| s.bytes_sent).sum(),
+                },
+            );
+        }
+        snapshots
+    }
+
+    fn status(&self) -> Option<String> {
+        let parts: Vec<String> = self
+            .primary
+            .status()
+            .into_iter()
+            .chain(self.warnings.iter().cloned())
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("; "))
+    }
+
+    fn link_up(&self, name: &str) -> Option<bool> {
+        self.primary.link_up(name)
+    }
+
+    fn refresh_devices(&mut self) {
+        self.primary.refresh_devices();
+    }
+}
@@ -0,0 +1,25 @@
+//! winload — Network Load Monitor 核心库
+//! 提供网卡流量采集、统计引擎与格式化工具，供 winload TUI 及第三方程序复用。
+//!
+//! ```no_run
+//! use std::{thread, time::Duration};
+//! use winload::{Collector, StatisticsEngine};
+//!
+//! let mut collector = Collector::new();
+//! let device = collector.devices().into_iter().next().expect("no device");
+//! let mut engine = StatisticsEngine::new(500, 300);
+//! for _ in 0..4 {
+//!     if let Some(snap) = collector.collect().remove(&device.key()) {
+//!         engine.update(snap);
+//!     }
+//!     thread::sleep(Duration::from_millis(500));
+//! }
+//! println!("{} in: {}", device.name, winload::stats::format_speed_unit(engine.incoming.current, winload::Unit::Bit));
+//! ```
+
+pub mod collector;
+pub mod graph;
+pub mod stats;
+
+pub use collector::{Collector, DeviceInfo, DeviceKey, Snapshot, TrafficSource};
+pub use stats::{EngineSnapshot, RateInputs, Spike, StatisticsEngine, TrafficStats, Unit};
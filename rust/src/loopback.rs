@@ -10,6 +10,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 /// Npcap 下载地址
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 pub const NPCAP_URL: &str = "https://npcap.com/#download";
 
 /// 回环流量计数器 (线程安全，可在采集线程和主线程之间共享)
@@ -19,6 +20,12 @@ pub struct LoopbackCounters {
     pub bytes_sent: Arc<AtomicU64>,
 }
 
+impl Default for LoopbackCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LoopbackCounters {
     pub fn new() -> Self {
         Self {
@@ -5,38 +5,133 @@
 //!     winload              # 监控所有活跃网卡
 //!     winload -t 200       # 设置刷新间隔 200ms
 //!     winload -d "Wi-Fi"   # 指定默认设备
+//!     winload -d eth0,wlan0  # 只在这两个网卡之间切换
+//!     WINLOAD_DEVICE=eth0 winload  # 用环境变量指定默认设备 (命令行 -d 优先)
+//!     winload --config my.toml     # 从指定的配置文件读取选项 (默认 ~/.config/winload/config.toml)
 //!
 //! 快捷键:
 //!     ←/→ 或 ↑/↓   切换网卡
-//!     q / Esc       退出
+//!     Tab / ⇧Tab    循环切换网卡
+//!     * / g         收藏当前网卡 / 跳到下一个收藏的网卡
+//!     m             总览: 网格中同时显示所有网卡
+//!     p / o         进程页: 按进程的带宽排行 / 切换排序列
+//!     Enter         总览中打开选中的网卡
+//!     u             切换 Bit/Byte 单位
+//!     k             切换单位前缀: nload (1024) / SI (1000, kB) / IEC (1024, KiB)
+//!     t             切换 Ttl 精确字节数显示
+//!     a             切换标题地址: IPv4+IPv6 / IPv4 / IPv6
+//!     f             冻结 / 恢复图形 (统计照常更新)
+//!     [ / ]         调整上下面板比例
+//!     + / - / 0     缩小 / 放大 / 重置图形时间窗口
+//!     x             交换 --compare 的左右两栏
+//!     i             显示 / 隐藏调试信息浮层 (同 --debug-info)
+//!     l             显示 / 隐藏图例
+//!     v             显示 / 隐藏速率计算浮层 (原始计数、增量、时长)
+//!     s             显示 / 隐藏峰值记录
+//!     c             复制当前网卡统计到剪贴板
+//!     e             导出当前网卡的速率历史到 CSV 文件
+//!     q / Esc       退出 (--confirm-quit 时先询问)
 
-mod collector;
-mod graph;
+mod alertlog;
+mod backend;
+mod clipboard;
+mod command;
+mod config;
+mod export;
+mod filter;
+mod hybrid;
 mod loopback;
-mod stats;
+mod netns;
+mod process;
+mod proto;
+mod record;
+mod remote;
+mod socket;
+mod state;
 mod ui;
 
+use std::collections::HashMap;
 use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
-use clap::Parser;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
-use collector::{Collector, DeviceInfo};
-use loopback::{LoopbackCounters, LoopbackMode};
-use stats::StatisticsEngine;
+use winload::collector::{self, Collector, DeviceInfo, DeviceKey, Snapshot, TrafficSource};
+use winload::graph::{Downsample, GraphChars};
+use winload::stats::{self, AvgMethod, StatisticsEngine, TrafficStats};
+use winload::Unit;
+
+use crate::alertlog::{AlertLevel, AlertLog};
+use crate::backend::CounterBackend;
+use crate::command::CommandSource;
+use crate::config::Value;
+use crate::filter::PacketFilter;
+use crate::hybrid::HybridSource;
+use crate::loopback::{LoopbackCounters, LoopbackMode};
+use crate::process::{ProcessMonitor, ProcessRate, ProcessSort};
+use crate::proto::ProtoSource;
+use crate::record::{Recorder, ReplaySource};
+use crate::socket::MetricsSocket;
+use crate::state::SavedHistory;
 
 // ─── 单位枚举 ─────────────────────────────────────────────
 
-/// 显示单位
+/// 显示单位 (--unit)，对应 [`Unit`]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
-pub enum Unit {
+pub enum UnitArg {
     /// 以 Bit/s 显示速率 (默认)
     Bit,
     /// 以 Byte/s 显示速率
     Byte,
+    /// 以 bit/s 显示速率，SI 前缀 (k = 1000)
+    BitSi,
+    /// 以 Byte/s 显示速率，SI 前缀 (k = 1000)
+    ByteSi,
+    /// 以 bit/s 显示速率，IEC 前缀 (Ki = 1024)
+    BitIec,
+    /// 以 Byte/s 显示速率，IEC 前缀 (Ki = 1024)
+    ByteIec,
+}
+
+impl From<UnitArg> for Unit {
+    fn from(unit: UnitArg) -> Self {
+        match unit {
+            UnitArg::Bit => Unit::Bit,
+            UnitArg::Byte => Unit::Byte,
+            UnitArg::BitSi => Unit::BitSi,
+            UnitArg::ByteSi => Unit::ByteSi,
+            UnitArg::BitIec => Unit::BitIec,
+            UnitArg::ByteIec => Unit::ByteIec,
+        }
+    }
+}
+
+/// 历史压缩到图形宽度时每列合并样本的方式 (--downsample)，对应 [`Downsample`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum DownsampleArg {
+    /// 取列内最大值，保留尖峰
+    Max,
+    /// 取列内平均值，显示平滑趋势
+    Avg,
+    /// 取列内最新的样本
+    Last,
 }
 
+impl From<DownsampleArg> for Downsample {
+    fn from(mode: DownsampleArg) -> Self {
+        match mode {
+            DownsampleArg::Max => Downsample::Max,
+            DownsampleArg::Avg => Downsample::Avg,
+            DownsampleArg::Last => Downsample::Last,
+        }
+    }
+}
+
+// ─── 样式枚举 ─────────────────────────────────────────────
+
 /// 状态栏/帮助栏样式
 #[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
 pub enum BarStyle {
@@ -48,6 +143,79 @@ pub enum BarStyle {
     Plain,
 }
 
+/// 状态栏 (--status) 可选字段
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatusField {
+    /// 所有网卡的总收发速率
+    Total,
+    /// 有流量的网卡数 / 网卡总数
+    Devices,
+    /// 已运行时长
+    Elapsed,
+    /// 当前显示单位
+    Unit,
+    /// 图形冻结标记 (仅冻结时显示)
+    Frozen,
+    /// 当前网卡的采样间隔与平均窗口，如 "t=500ms a=300s"
+    Timing,
+    /// 当前网卡平均速率的收发占比
+    Ratio,
+}
+
+/// Avg 的计算方式 (--avg-method)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum AvgMethodArg {
+    /// 平均窗口内的简单平均
+    Sma,
+    /// 指数移动平均 (系数见 --ema-alpha)
+    Ema,
+}
+
+/// 图形空白格的背景字符 (--bg-char)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum BgChar {
+    /// 空白 (默认)
+    Space,
+    /// 点: `.` / `·` (-U)
+    Dot,
+    /// 横线: `-` / `─` (-U)
+    Dash,
+}
+
+impl BgChar {
+    pub fn glyph(self, unicode: bool) -> char {
+        match (self, unicode) {
+            (BgChar::Space, _) => ' ',
+            (BgChar::Dot, false) => '.',
+            (BgChar::Dot, true) => '\u{b7}',
+            (BgChar::Dash, false) => '-',
+            (BgChar::Dash, true) => '\u{2500}',
+        }
+    }
+}
+
+/// 标题栏显示的地址族 (a 键切换)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddrFamily {
+    /// IPv4 与 IPv6 都显示 (默认)
+    Both,
+    /// 仅 IPv4
+    V4,
+    /// 仅 IPv6
+    V6,
+}
+
+impl AddrFamily {
+    /// 切换到下一个: Both → V4 → V6 → Both
+    fn next(self) -> Self {
+        match self {
+            AddrFamily::Both => AddrFamily::V4,
+            AddrFamily::V4 => AddrFamily::V6,
+            AddrFamily::V6 => AddrFamily::Both,
+        }
+    }
+}
+
 /// 解析人类可读的流量值，如 "100M" → 100*1024*1024 bytes/s
 pub fn parse_max_value(s: &str) -> Result<f64, String> {
     let s = s.trim();
@@ -64,9 +232,35 @@ pub fn parse_max_value(s: &str) -> Result<f64, String> {
         (s, 1.0)
     };
     let num: f64 = num_str.parse().map_err(|e| format!("invalid number: {e}"))?;
+    // "inf" / "nan" 也能被解析，这里一并拒绝
+    if !num.is_finite() || num < 0.0 {
+        return Err(format!("expected a non-negative number, got: {s}"));
+    }
     Ok(num * multiplier)
 }
 
+/// 解析 -m 图形上限: 同 [`parse_max_value`]，但必须大于 0 (刻度会用作除数)
+fn parse_graph_max(s: &str) -> Result<f64, String> {
+    let value = parse_max_value(s)?;
+    if value <= 0.0 {
+        return Err("graph max must be greater than 0".to_string());
+    }
+    Ok(value)
+}
+
+/// 解析单网卡刷新间隔，格式 "NAME=MS"，如 "VPN=2000"
+pub fn parse_device_interval(s: &str) -> Result<(String, u64), String> {
+    let (name, ms) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected NAME=MS (e.g. Wi-Fi=200), got: {s}"))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("missing device name in: {s}"));
+    }
+    let ms: u64 = ms.trim().parse().map_err(|e| format!("invalid interval: {e}"))?;
+    Ok((name.to_string(), ms))
+}
+
 /// 解析十六进制颜色码，支持 0xRRGGBB 或 RRGGBB 格式
 pub fn parse_hex_color(s: &str) -> Result<ratatui::style::Color, String> {
     let hex = s.trim().strip_prefix("0x").or_else(|| s.trim().strip_prefix("0X")).unwrap_or(s.trim());
@@ -79,28 +273,281 @@ pub fn parse_hex_color(s: &str) -> Result<ratatui::style::Color, String> {
     Ok(ratatui::style::Color::Rgb(r, g, b))
 }
 
+// ─── 配置文件 ──────────────────────────────────────────────
+
+/// 可在配置文件 `[keys]` 表中重映射的按键动作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KeyAction {
+    NextDevice,
+    PrevDevice,
+    Quit,
+    Unit,
+    Prefix,
+    Total,
+    Address,
+    Freeze,
+    Shrink,
+    Grow,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    Swap,
+    Info,
+    Legend,
+    Rates,
+    Spikes,
+    Copy,
+    Export,
+    Star,
+    NextStar,
+    Overview,
+    Processes,
+    Sort,
+}
+
+/// 每个动作在配置文件中的名字及其默认按键
+const KEY_ACTIONS: &[(&str, KeyAction, &[KeyCode])] = &[
+    ("next-device", KeyAction::NextDevice, &[KeyCode::Right, KeyCode::Down, KeyCode::Tab]),
+    ("prev-device", KeyAction::PrevDevice, &[KeyCode::Left, KeyCode::Up, KeyCode::BackTab]),
+    ("quit", KeyAction::Quit, &[KeyCode::Char('q'), KeyCode::Char('Q'), KeyCode::Esc]),
+    ("unit", KeyAction::Unit, &[KeyCode::Char('u'), KeyCode::Char('U')]),
+    ("prefix", KeyAction::Prefix, &[KeyCode::Char('k'), KeyCode::Char('K')]),
+    ("total", KeyAction::Total, &[KeyCode::Char('t'), KeyCode::Char('T')]),
+    ("address", KeyAction::Address, &[KeyCode::Char('a'), KeyCode::Char('A')]),
+    ("freeze", KeyAction::Freeze, &[KeyCode::Char('f'), KeyCode::Char('F')]),
+    ("shrink", KeyAction::Shrink, &[KeyCode::Char('[')]),
+    ("grow", KeyAction::Grow, &[KeyCode::Char(']')]),
+    ("zoom-in", KeyAction::ZoomIn, &[KeyCode::Char('+'), KeyCode::Char('=')]),
+    ("zoom-out", KeyAction::ZoomOut, &[KeyCode::Char('-')]),
+    ("zoom-reset", KeyAction::ZoomReset, &[KeyCode::Char('0')]),
+    ("swap", KeyAction::Swap, &[KeyCode::Char('x'), KeyCode::Char('X')]),
+    ("info", KeyAction::Info, &[KeyCode::Char('i'), KeyCode::Char('I')]),
+    ("legend", KeyAction::Legend, &[KeyCode::Char('l'), KeyCode::Char('L')]),
+    ("rates", KeyAction::Rates, &[KeyCode::Char('v'), KeyCode::Char('V')]),
+    ("spikes", KeyAction::Spikes, &[KeyCode::Char('s'), KeyCode::Char('S')]),
+    ("copy", KeyAction::Copy, &[KeyCode::Char('c'), KeyCode::Char('C')]),
+    ("export", KeyAction::Export, &[KeyCode::Char('e'), KeyCode::Char('E')]),
+    ("star", KeyAction::Star, &[KeyCode::Char('*')]),
+    ("next-star", KeyAction::NextStar, &[KeyCode::Char('g'), KeyCode::Char('G')]),
+    ("overview", KeyAction::Overview, &[KeyCode::Char('m'), KeyCode::Char('M')]),
+    ("processes", KeyAction::Processes, &[KeyCode::Char('p'), KeyCode::Char('P')]),
+    ("sort", KeyAction::Sort, &[KeyCode::Char('o'), KeyCode::Char('O')]),
+];
+
+/// 由默认按键与配置文件的重映射 (动作, 字符) 生成按键表
+///
+/// 重映射的动作改用新字符 (字母不分大小写)，不再响应默认的字符键；方向键、Tab、Esc
+/// 等非字符键保留。同一个按键对应两个动作时返回错误。
+fn key_bindings(overrides: &[(KeyAction, char)]) -> Result<Vec<(KeyCode, KeyAction)>, String> {
+    let mut table: Vec<(KeyCode, KeyAction)> = Vec::new();
+    for &(name, action, defaults) in KEY_ACTIONS {
+        let keys: Vec<KeyCode> = match overrides.iter().find(|(a, _)| *a == action) {
+            Some(&(_, ch)) => {
+                let mut chars = vec![ch.to_ascii_lowercase(), ch.to_ascii_uppercase()];
+                chars.dedup();
+                defaults
+                    .iter()
+                    .copied()
+                    .filter(|k| !matches!(k, KeyCode::Char(_)))
+                    .chain(chars.into_iter().map(KeyCode::Char))
+                    .collect()
+            }
+            None => defaults.to_vec(),
+        };
+        for key in keys {
+            if let Some(&(_, other)) = table.iter().find(|(k, _)| *k == key) {
+                let other = KEY_ACTIONS.iter().find(|(_, a, _)| *a == other).map_or("", |(n, _, _)| *n);
+                let key = match key {
+                    KeyCode::Char(c) => c.to_string(),
+                    key => format!("{key:?}"),
+                };
+                return Err(format!("key '{key}' is bound to both '{other}' and '{name}'"));
+            }
+            table.push((key, action));
+        }
+    }
+    Ok(table)
+}
+
+/// 解析命令行并合并配置文件，得到最终的参数
+///
+/// 优先级: 命令行 > 环境变量 > 配置文件 > 默认值。配置文件中的选项按长选项名
+/// 转成命令行参数，插在真正的命令行参数之前，由 clap 统一校验。
+fn load_args() -> Args {
+    let cli: Vec<std::ffi::OsString> = std::env::args_os().collect();
+    let matches = Args::command().get_matches_from(&cli);
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if args.no_config {
+        return args;
+    }
+    // --config 指定的文件必须存在；默认位置没有文件时视为没有配置
+    let (path, required) = match args.config.clone() {
+        Some(path) => (path, true),
+        None => match config::default_path() {
+            Some(path) => (path, false),
+            None => return args,
+        },
+    };
+    let config = match config::load(&path, required) {
+        Ok(Some(config)) => config,
+        Ok(None) => return args,
+        Err(e) => {
+            eprintln!("Error: config {e}");
+            std::process::exit(2);
+        }
+    };
+    let fail = |msg: String| -> ! {
+        eprintln!("Error: config {}: {msg}", path.display());
+        std::process::exit(2);
+    };
+
+    let command = Args::command();
+    let mut argv = vec![cli.first().cloned().unwrap_or_else(|| "winload".into())];
+    for (key, value) in &config.options {
+        let Some(arg) = command
+            .get_arguments()
+            .find(|a| a.get_long() == Some(key.as_str()))
+            .filter(|_| !matches!(key.as_str(), "config" | "no-config"))
+        else {
+            fail(format!("unknown option '{key}'"));
+        };
+        // 命令行或环境变量已给出的选项以它们为准
+        let source = matches.value_source(arg.get_id().as_str());
+        if matches!(source, Some(ValueSource::CommandLine | ValueSource::EnvVariable)) {
+            continue;
+        }
+        match value {
+            Value::Bool(true) => argv.push(format!("--{key}").into()),
+            Value::Bool(false) => {}
+            Value::Text(text) => argv.push(format!("--{key}={text}").into()),
+            Value::List(items) => argv.extend(items.iter().map(|t| format!("--{key}={t}").into())),
+        }
+    }
+    argv.extend(cli.iter().skip(1).cloned());
+    let mut args = match Args::try_parse_from(&argv) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: invalid option in config {}:", path.display());
+            e.exit();
+        }
+    };
+
+    let mut overrides = Vec::new();
+    for (action, key) in &config.keys {
+        let Some(&(_, action_id, _)) = KEY_ACTIONS.iter().find(|(name, _, _)| name == action) else {
+            let names: Vec<&str> = KEY_ACTIONS.iter().map(|(name, _, _)| *name).collect();
+            fail(format!("unknown key action '{action}' (expected one of: {})", names.join(", ")));
+        };
+        let mut chars = key.chars();
+        let (Some(ch), None) = (chars.next(), chars.next()) else {
+            fail(format!("key for '{action}' must be a single character, got \"{key}\""));
+        };
+        overrides.push((action_id, ch));
+    }
+    args.keymap = key_bindings(&overrides).unwrap_or_else(|e| fail(e));
+    args
+}
+
 // ─── CLI 参数 ──────────────────────────────────────────────
 
 /// Network Load Monitor — nload-like TUI tool
 #[derive(Parser)]
 #[command(name = "winload", version, about)]
+#[command(group(clap::ArgGroup::new("alerts").multiple(true).args(["idle_alert", "alert_rx", "alert_tx"])))]
 struct Args {
     /// Refresh interval in milliseconds
-    #[arg(short = 't', long = "interval", default_value = "500")]
+    #[arg(short = 't', long = "interval", env = "WINLOAD_INTERVAL", default_value = "500")]
     interval: u64,
 
     /// Average window in seconds
-    #[arg(short = 'a', long = "average", default_value = "300")]
+    #[arg(short = 'a', long = "average", env = "WINLOAD_AVERAGE", default_value = "300")]
     average: u64,
 
-    /// Default device name (partial match)
-    #[arg(short = 'd', long = "device")]
-    device: Option<String>,
+    /// Default device name (partial match). Repeat or comma-separate to pin a subset,
+    /// e.g. -d eth0,wlan0 — only matching devices are cycled
+    #[arg(short = 'd', long = "device", env = "WINLOAD_DEVICE", value_delimiter = ',')]
+    device: Vec<String>,
+
+    /// Default device by OS interface index (stable across renames; see --debug-info).
+    /// Takes precedence over -d; ignored if no interface has that index
+    #[arg(long = "index", value_name = "N")]
+    index: Option<u32>,
+
+    /// Per-device refresh interval NAME=MS (partial match, repeatable), e.g. VPN=2000
+    #[arg(long = "device-interval", value_name = "NAME=MS", value_parser = parse_device_interval)]
+    device_interval: Vec<(String, u64)>,
+
+    /// Swap which raw counter is shown as incoming vs outgoing, for tunnels and virtual
+    /// adapters that label directions the other way round. Without NAME applies to all
+    /// devices; with NAME (partial match, comma-separated) only to matching ones
+    #[arg(long = "swap-direction", value_name = "NAME", num_args = 0.., value_delimiter = ',')]
+    swap_direction: Option<Vec<String>>,
+
+    /// Mark devices as favorites (partial match, comma-separated), in addition to those
+    /// starred with * in earlier runs; g cycles through favorites only
+    #[arg(long = "favorite", value_name = "NAME", value_delimiter = ',')]
+    favorite: Vec<String>,
+
+    /// Median-filter the current speed over N samples (e.g. 3 or 5) to reject one-tick spikes
+    #[arg(long = "median", value_name = "N", value_parser = clap::value_parser!(u8).range(3..=9))]
+    median: Option<u8>,
+
+    /// Smooth the plotted graph (not the statistics) over ±N samples to calm TCP sawtooth;
+    /// without N uses 2
+    #[arg(long = "display-smooth", value_name = "N", num_args = 0..=1, default_missing_value = "2",
+          value_parser = clap::value_parser!(u8).range(1..=10))]
+    display_smooth: Option<u8>,
+
+    /// Fit the whole retained history into the graph width, combining samples per column
+    /// by max (keeps spikes), avg (smooth trend) or last; without MODE uses max
+    #[arg(long = "downsample", value_name = "MODE", value_enum, num_args = 0..=1, default_missing_value = "max")]
+    downsample: Option<DownsampleArg>,
+
+    /// How the Avg line is computed: sma (steady, over the --average window) or ema (reacts faster)
+    #[arg(long = "avg-method", value_name = "METHOD", value_enum, default_value_t = AvgMethodArg::Sma)]
+    avg_method: AvgMethodArg,
+
+    /// EMA smoothing factor per sample, in (0, 1]; higher follows recent samples more closely
+    #[arg(long = "ema-alpha", value_name = "ALPHA", default_value_t = 0.2)]
+    ema_alpha: f64,
+
+    /// Read options from this TOML file instead of the default config file
+    /// (~/.config/winload/config.toml, or %APPDATA%\winload\config.toml on Windows)
+    #[arg(long = "config", value_name = "PATH", env = "WINLOAD_CONFIG")]
+    config: Option<std::path::PathBuf>,
+
+    /// Ignore the config file
+    #[arg(long = "no-config")]
+    no_config: bool,
+
+    /// 按键表: 默认按键加上配置文件 [keys] 表的重映射
+    #[arg(skip = key_bindings(&[]).expect("default key bindings conflict"))]
+    keymap: Vec<(KeyCode, KeyAction)>,
 
     /// Print debug info about network interfaces and exit
     #[arg(long = "debug-info")]
     debug_info: bool,
 
+    /// Print network interfaces as a JSON array (name, addrs, mac, type, link speed) and exit
+    #[arg(long = "devices-json")]
+    devices_json: bool,
+
+    /// How to read interface counters: auto (default), or on Linux procfs (/proc/net/dev)
+    /// or netlink (RTM_GETLINK 64-bit stats)
+    #[arg(long = "backend", value_name = "BACKEND", value_enum, default_value_t = CounterBackend::Auto)]
+    backend: CounterBackend,
+
+    /// Linux: enter the network namespace NAME (from `ip netns`, or a path such as
+    /// /proc/PID/ns/net) before listing and collecting interfaces. Needs root
+    #[arg(long = "netns", value_name = "NAME", conflicts_with_all = ["remote", "source_cmd", "replay"])]
+    netns: Option<String>,
+
+    /// Benchmark the collector: call it as fast as possible for SECS (default 5), print
+    /// collections per second and latency, then exit (no UI)
+    #[arg(long = "bench", value_name = "SECS", num_args = 0..=1, default_missing_value = "5",
+          value_parser = clap::value_parser!(u64).range(1..=3600), hide = true)]
+    bench: Option<u64>,
+
     /// Enable emoji decorations in TUI and output
     #[arg(short = 'e', long = "emoji")]
     emoji: bool,
@@ -109,9 +556,19 @@ struct Args {
     #[arg(short = 'U', long = "unicode")]
     unicode: bool,
 
-    /// Display unit: bit (default) or byte
+    /// Graph characters: preset (ascii, block, shade, eighths) or "full,partial,empty" (e.g. "=,-, ")
+    #[arg(long = "graph-chars", value_name = "CHARS", value_parser = GraphChars::parse)]
+    graph_chars: Option<GraphChars>,
+
+    /// Character drawn in the graph's empty cells: space (default), dot or dash.
+    /// Overrides the empty glyph of --graph-chars
+    #[arg(long = "bg-char", value_name = "CHAR", value_enum)]
+    bg_char: Option<BgChar>,
+
+    /// Display unit: bit (default) or byte, with nload-style 1024 prefixes (kBit/s, KB/s);
+    /// the -si variants use decimal prefixes (1 kB = 1000 B), the -iec variants KiB/MiB
     #[arg(short = 'u', long = "unit", value_enum, default_value = "bit")]
-    unit: Unit,
+    unit: UnitArg,
 
     /// Bar style for header/label/help: fill (default), color, plain
     #[arg(short = 'b', long = "bar-style", value_enum, default_value = "fill")]
@@ -126,13 +583,230 @@ struct Args {
     out_color: Option<ratatui::style::Color>,
 
     /// Fixed graph Y-axis max (e.g. 100M, 1G, 500K). Default: auto-scale
-    #[arg(short = 'm', long = "max", value_parser = parse_max_value)]
+    #[arg(short = 'm', long = "max", value_parser = parse_graph_max)]
     max: Option<f64>,
 
+    /// Redraw rate in frames per second, independent of the sampling interval (1-240)
+    #[arg(long = "fps", value_parser = clap::value_parser!(u32).range(1..=240))]
+    fps: Option<u32>,
+
+    /// Background traffic to subtract before plotting (e.g. 10K); bars show only traffic above it
+    #[arg(long = "noise-floor", value_name = "SPEED", value_parser = parse_max_value)]
+    noise_floor: Option<f64>,
+
+    /// Also subtract --noise-floor from the Curr/Avg/Min/Max statistics
+    #[arg(long = "noise-floor-stats", requires = "noise_floor")]
+    noise_floor_stats: bool,
+
+    /// Bottom of the graph's value range (e.g. 10M): lower speeds draw empty, so the band
+    /// of interest uses the full height
+    #[arg(long = "crop-min", value_name = "SPEED", value_parser = parse_max_value)]
+    crop_min: Option<f64>,
+
+    /// Top of the graph's value range (e.g. 100M): higher speeds clip to full height
+    #[arg(long = "crop-max", value_name = "SPEED", value_parser = parse_graph_max, conflicts_with = "max")]
+    crop_max: Option<f64>,
+
+    /// Use one graph scale for both panels so incoming and outgoing are directly comparable
+    #[arg(long = "shared-scale")]
+    shared_scale: bool,
+
+    /// Show every speed in a panel with the unit of its graph scale (e.g. always MBit/s),
+    /// changing only when the scale does, instead of picking kBit/MBit per value
+    #[arg(long = "fixed-unit")]
+    fixed_unit: bool,
+
+    /// Show two devices side by side with one shared scale (partial match), e.g. "wlan,eth";
+    /// arrow keys change the left device, x swaps the two columns
+    #[arg(long = "compare", value_name = "A,B", value_delimiter = ',')]
+    compare: Vec<String>,
+
+    /// Show an ETA for receiving this many bytes this session (e.g. 4G), based on average incoming speed
+    #[arg(long = "eta-target", value_name = "BYTES", value_parser = parse_max_value)]
+    eta_target: Option<f64>,
+
+    /// Exit automatically after SECONDS (e.g. for timed measurements). Exit code: 0 normal,
+    /// 1 an alert fired, 2 the shown device is down or has no data
+    #[arg(long = "duration", value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    duration: Option<u64>,
+
+    /// Print a per-device stats summary to stdout on exit
+    #[arg(long = "summary")]
+    summary: bool,
+
+    /// On exit write a JSON report of every device (totals, peak/average speeds, duration)
+    /// to PATH, or to stdout without a PATH
+    #[arg(long = "report-json", value_name = "PATH", num_args = 0..=1, default_missing_value = "-")]
+    report_json: Option<std::path::PathBuf>,
+
+    /// Show bytes transferred in the last SECONDS as an extra stats line
+    #[arg(long = "window-total", value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..=86400))]
+    window_total: Option<u64>,
+
+    /// Show the peak speed of the last SECONDS (default 30) as an extra stats line; unlike
+    /// Max it drops back once a burst leaves the window
+    #[arg(long = "recent-max", value_name = "SECONDS", num_args = 0..=1, default_missing_value = "30",
+          value_parser = clap::value_parser!(u64).range(1..=86400))]
+    recent_max: Option<u64>,
+
     /// Hide traffic graphs, show only statistics
     #[arg(short = 'n', long = "no-graph")]
     no_graph: bool,
 
+    /// Fade older graph columns so the most recent activity stands out
+    #[arg(long = "fade")]
+    fade: bool,
+
+    /// Show a mini graph of bytes transferred this session below each traffic graph
+    #[arg(long = "cumulative")]
+    cumulative: bool,
+
+    /// Percentage of the height given to the incoming panel (10-90)
+    #[arg(long = "split", value_name = "IN%", default_value_t = 50, value_parser = clap::value_parser!(u16).range(SPLIT_MIN as i64..=SPLIT_MAX as i64))]
+    split: u16,
+
+    /// Replace the graph with a utilization gauge (percent of --max)
+    #[arg(long = "gauge", requires = "max", conflicts_with = "no_graph")]
+    gauge: bool,
+
+    /// Thousands separator for exact totals (t key): a single character, "none", or "auto" (from locale)
+    #[arg(long = "thousands-sep", value_name = "SEP", default_value = "auto")]
+    thousands_sep: String,
+
+    /// Print the current value above the newest graph column
+    #[arg(long = "live-value")]
+    live_value: bool,
+
+    /// Show a legend for the graph's symbols and colors (toggle with l)
+    #[arg(long = "legend")]
+    legend: bool,
+
+    /// Start with the rate-inputs overlay shown: raw counters, delta and elapsed time
+    /// behind the current speed (toggle with v)
+    #[arg(long = "verbose")]
+    verbose: bool,
+
+    /// Keep the N biggest bursts per direction for the spike log (toggle with s; printed
+    /// with --summary). Samples less than 10s apart count as one burst
+    #[arg(long = "spikes", value_name = "N", default_value_t = 5,
+          value_parser = clap::value_parser!(u16).range(1..=50))]
+    spikes: u16,
+
+    /// Show a "next in 0.3s" countdown to the next sample in the help bar
+    #[arg(long = "countdown")]
+    countdown: bool,
+
+    /// Ask "Quit? (y/n)" before q / Esc exits, so a long session isn't lost to a stray key
+    /// (Ctrl-C still quits immediately)
+    #[arg(long = "confirm-quit")]
+    confirm_quit: bool,
+
+    /// Draw in an inline viewport below the prompt instead of the alternate screen;
+    /// the last frame stays in the scrollback after quitting
+    #[arg(long = "inline")]
+    inline: bool,
+
+    /// Height of the --inline viewport in lines
+    #[arg(long = "lines", value_name = "N", default_value_t = 20, requires = "inline",
+          value_parser = clap::value_parser!(u16).range(3..=500))]
+    lines: u16,
+
+    /// Mark the graph's scale ceiling with a dashed line and its value along the top row
+    #[arg(long = "show-ceiling", conflicts_with = "no_graph")]
+    show_ceiling: bool,
+
+    /// Draw a faint vertical line every SECONDS of sample time behind the graph, so a flat
+    /// plateau still shows how long it has lasted
+    #[arg(long = "time-grid", value_name = "SECONDS", conflicts_with = "no_graph",
+          value_parser = clap::value_parser!(u64).range(1..=86400))]
+    time_grid: Option<u64>,
+
+    /// Color graph cells by height (green → yellow → red) instead of a flat color
+    #[arg(long = "heat")]
+    heat: bool,
+
+    /// Show a status bar above the help bar; optionally pick fields (default: all)
+    #[arg(
+        long = "status",
+        value_name = "FIELDS",
+        value_enum,
+        value_delimiter = ',',
+        num_args = 0..,
+        default_missing_values = ["total", "devices", "elapsed", "unit", "frozen", "timing", "ratio"]
+    )]
+    status: Vec<StatusField>,
+
+    /// Hide the machine-wide total throughput (Σ) in the header
+    #[arg(long = "no-total")]
+    no_total: bool,
+
+    /// Keep the panel labels to the graph scale, without the current speed appended
+    #[arg(long = "no-label-rate")]
+    no_label_rate: bool,
+
+    /// Start with empty graphs instead of the history saved by the previous run
+    #[arg(long = "no-restore-history")]
+    no_restore_history: bool,
+
+    /// Alert (beep once + flashing header) when a device shows zero traffic for SECONDS
+    #[arg(long = "idle-alert", value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    idle_alert: Option<u64>,
+
+    /// Alert (beep once + header warning) when the shown device's incoming speed exceeds SPEED (e.g. 10M)
+    #[arg(long = "alert-rx", value_name = "SPEED", value_parser = parse_max_value)]
+    alert_rx: Option<f64>,
+
+    /// Alert when the shown device's outgoing speed exceeds SPEED
+    #[arg(long = "alert-tx", value_name = "SPEED", value_parser = parse_max_value)]
+    alert_tx: Option<f64>,
+
+    /// Show the top N processes by current TCP throughput in a table (Linux; Windows as
+    /// administrator)
+    #[arg(long = "top", value_name = "N", value_parser = clap::value_parser!(u16).range(1..=50))]
+    top: Option<u16>,
+
+    /// Also record alert edges (fired / cleared) in syslog or the Windows Event Log; needs
+    /// --idle-alert, --alert-rx or --alert-tx
+    #[arg(long = "log-alerts", requires = "alerts")]
+    log_alerts: bool,
+
+    /// Monitor a remote Linux host's interfaces over ssh (key-based login required)
+    #[arg(long = "remote", value_name = "USER@HOST", conflicts_with_all = ["pcap", "npcap", "etw"])]
+    remote: Option<String>,
+
+    /// Read counters from a command's output each sample; lines are "<device> <rx_bytes> <tx_bytes>"
+    #[arg(long = "source-cmd", value_name = "CMD", conflicts_with_all = ["remote", "replay", "pcap", "npcap", "etw"])]
+    source_cmd: Option<String>,
+
+    /// Per-device backend NAME=BACKEND (partial match, repeatable): os (default) or pcap,
+    /// e.g. --device-source eth1=pcap reads eth1 by packet capture and the rest from the OS
+    #[arg(long = "device-source", value_name = "NAME=BACKEND", value_parser = hybrid::parse_device_source, value_delimiter = ',',
+          conflicts_with_all = ["remote", "source_cmd", "replay"])]
+    device_source: Vec<(String, hybrid::Backend)>,
+
+    /// Count only packets matching a BPF-style filter on captured devices, e.g.
+    /// "host 10.0.0.5 and port 443". Captures the -d devices unless --device-source is given
+    #[arg(long = "filter", value_name = "EXPR", value_parser = PacketFilter::parse,
+          conflicts_with_all = ["remote", "source_cmd", "replay"])]
+    filter: Option<PacketFilter>,
+
+    /// Record raw counters of every sample to FILE for later --replay
+    #[arg(long = "record", value_name = "FILE")]
+    record: Option<std::path::PathBuf>,
+
+    /// Replay a session recorded with --record instead of reading live counters
+    #[arg(long = "replay", value_name = "FILE", conflicts_with_all = ["record", "remote", "pcap", "npcap", "etw"])]
+    replay: Option<std::path::PathBuf>,
+
+    /// Broadcast per-device metrics as NDJSON on a Unix socket at PATH after each sample
+    #[arg(long = "socket", value_name = "PATH")]
+    socket: Option<std::path::PathBuf>,
+
+    /// Split the selected device's traffic by protocol (TCP/UDP) via packet capture.
+    /// Needs root/CAP_NET_RAW on Linux or Npcap + administrator on Windows
+    #[arg(long = "pcap")]
+    pcap: bool,
+
     /// [Windows only] Use Npcap to capture loopback traffic (recommended)
     /// Requires Npcap installed: https://npcap.com/#download
     #[arg(long = "npcap", conflicts_with = "etw")]
@@ -148,57 +822,519 @@ struct Args {
     etw: bool,
 }
 
+/// 刷新间隔下限 (毫秒)，更小的值会让主循环空转占满一个核心
+const MIN_INTERVAL_MS: u64 = 10;
+/// 平均窗口上限 (秒)，即 24 小时
+const MAX_AVERAGE_SECS: u64 = 24 * 60 * 60;
+
+/// 刷新间隔的校验规则: 0 直接拒绝，过小的值钳制到下限
+fn clamp_interval(flag: &str, ms: u64) -> Result<u64, String> {
+    if ms == 0 {
+        return Err(format!("{flag} must be greater than 0 ms"));
+    }
+    Ok(ms.max(MIN_INTERVAL_MS))
+}
+
+/// 校验并修正刷新间隔，被钳制时输出警告
+fn validate_interval(flag: &str, ms: u64) -> Result<u64, String> {
+    let clamped = clamp_interval(flag, ms)?;
+    if clamped != ms {
+        eprintln!("Warning: {flag} {ms}ms is too small, using {clamped}ms");
+    }
+    Ok(clamped)
+}
+
+impl Args {
+    /// --avg-method / --ema-alpha 对应的统计方式
+    fn avg_method(&self) -> AvgMethod {
+        match self.avg_method {
+            AvgMethodArg::Sma => AvgMethod::Sma,
+            AvgMethodArg::Ema => AvgMethod::Ema {
+                alpha: self.ema_alpha,
+            },
+        }
+    }
+
+    /// --thousands-sep 对应的分隔符 (已通过 validate 校验)
+    fn thousands_sep(&self) -> Option<char> {
+        match self.thousands_sep.as_str() {
+            "auto" => Some(stats::locale_thousands_sep()),
+            "none" => None,
+            s => s.chars().next(),
+        }
+    }
+
+    /// `Args::parse` 之后的参数校验层
+    fn validate(&mut self) -> Result<(), String> {
+        if !matches!(self.thousands_sep.as_str(), "auto" | "none") && self.thousands_sep.chars().count() != 1 {
+            return Err(format!(
+                "--thousands-sep {:?} must be a single character, \"none\" or \"auto\"",
+                self.thousands_sep
+            ));
+        }
+        self.interval = validate_interval("--interval", self.interval)?;
+        for (name, ms) in &mut self.device_interval {
+            *ms = validate_interval(&format!("--device-interval {name}"), *ms)?;
+        }
+        if !(self.ema_alpha > 0.0 && self.ema_alpha <= 1.0) {
+            return Err(format!("--ema-alpha {} must be in (0, 1]", self.ema_alpha));
+        }
+        if self.average == 0 {
+            return Err("--average must be at least 1 second".to_string());
+        }
+        if !self.backend.available() {
+            return Err(format!("--backend {} is only available on Linux", self.backend.name()));
+        }
+        if let Some(max) = self.crop_max {
+            if self.crop_min.is_some_and(|min| min >= max) {
+                return Err("--crop-min must be below --crop-max".to_string());
+            }
+            if self.noise_floor.is_some_and(|floor| floor >= max) {
+                return Err("--noise-floor must be below --crop-max".to_string());
+            }
+        }
+        if !self.compare.is_empty() && self.compare.len() != 2 {
+            return Err("--compare expects exactly two device names, e.g. \"wlan,eth\"".to_string());
+        }
+        // --filter 未指定 --device-source 时，对 -d 选中的网卡抓包
+        if self.filter.is_some() && self.device_source.is_empty() {
+            if self.device.is_empty() {
+                return Err(
+                    "--filter needs a device to capture on: use -d NAME or --device-source NAME=pcap".to_string(),
+                );
+            }
+            self.device_source = self.device.iter().map(|d| (d.clone(), hybrid::Backend::Pcap)).collect();
+        }
+        if self.average > MAX_AVERAGE_SECS {
+            return Err(format!(
+                "--average {} is too large (max {MAX_AVERAGE_SECS}s = 24h)",
+                self.average
+            ));
+        }
+        Ok(())
+    }
+}
+
 // ─── App 状态 ──────────────────────────────────────────────
 
+/// 收方向面板高度占比的范围 (%)
+const SPLIT_MIN: u16 = 10;
+const SPLIT_MAX: u16 = 90;
+/// `[` / `]` 每次调整的步长 (%)
+const SPLIT_STEP: u16 = 5;
+/// 图形缩放级别的范围 (+ 键每级样本数减半，- 键加倍)
+const ZOOM_MIN: i8 = -6;
+const ZOOM_MAX: i8 = 4;
+
 /// 单个网卡的视图状态
 pub struct DeviceView {
     pub info: DeviceInfo,
     pub engine: StatisticsEngine,
+    /// 该网卡的采样间隔 (默认等于全局 --interval)
+    pub interval: Duration,
+    /// 上次喂入统计引擎的时间
+    last_sample: Option<Instant>,
+    /// 本轮静默是否已经响铃过 (恢复流量后重置)
+    idle_alerted: bool,
+    /// 连续采集不到快照的次数 (网卡暂时不可读或已消失)
+    missed: u32,
+    /// 交换收发计数器 (--swap-direction)
+    pub swapped: bool,
+    /// 收藏的网卡 (--favorite / * 键)，g 键只在收藏之间切换
+    pub favorite: bool,
+}
+
+/// 热插拔扫描的间隔: 每隔这么久重新枚举一次网卡，加入启动后才出现的网卡
+const DEVICE_RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 帮助栏短提示 (如复制结果) 的显示时长
+const NOTICE_DURATION: Duration = Duration::from_secs(2);
+
+/// --countdown 未指定 --fps 时的重绘间隔
+const COUNTDOWN_FRAME: Duration = Duration::from_millis(100);
+
+/// 调试信息浮层 PageUp / PageDown 每次滚动的行数
+const DEBUG_PAGE_LINES: u16 = 10;
+
+/// 连续缺失多少次快照后视为 "no data"
+const MISSED_SNAPSHOTS_LIMIT: u32 = 3;
+
+impl DeviceView {
+    /// 是否已连续多次采集不到数据
+    pub fn no_data(&self) -> bool {
+        self.missed >= MISSED_SNAPSHOTS_LIMIT
+    }
+
+    /// 是否已静默超过 --idle-alert 阈值
+    pub fn is_idle(&self, threshold: Option<Duration>) -> bool {
+        threshold.is_some_and(|t| self.engine.idle_secs() >= t.as_secs_f64())
+    }
+}
+
+/// --pcap 协议拆分视图: 每个协议一个统计引擎
+pub struct ProtoView {
+    /// 被抓包的网卡名
+    pub device: String,
+    /// (协议名, 统计引擎)，顺序同 [`proto::PROTOCOLS`]
+    pub engines: Vec<(String, StatisticsEngine)>,
+    source: ProtoSource,
+}
+
+impl ProtoView {
+    fn new(source: ProtoSource, args: &Args) -> Self {
+        let engines = source
+            .devices()
+            .into_iter()
+            .map(|d| {
+                let mut engine = StatisticsEngine::new(args.interval, args.average);
+                engine.set_avg_method(args.avg_method());
+                (d.name, engine)
+            })
+            .collect();
+        Self {
+            device: source.device.clone(),
+            engines,
+            source,
+        }
+    }
+
+    fn update(&mut self) {
+        let snapshots = self.source.collect();
+        for (name, engine) in &mut self.engines {
+            if let Some(snap) = snapshots.get(&DeviceKey::Name(name.clone())) {
+                engine.update(snap.clone());
+            }
+        }
+    }
+}
+
+/// 快照键对应的设备名: 名称键即设备名，索引键在 `names` 中查找
+fn key_name<'a>(names: &'a HashMap<u32, String>, key: &'a DeviceKey) -> Option<&'a str> {
+    match key {
+        DeviceKey::Index(index) => names.get(index).map(String::as_str),
+        DeviceKey::Name(name) => Some(name),
+    }
+}
+
+/// 按命令行参数为一个网卡创建视图 (启动时与热插拔发现新网卡时共用)
+///
+/// `saved_history` 为上次运行保存的速率历史，`saved_favorites` 为已收藏的网卡名。
+fn new_device_view(
+    args: &Args,
+    info: DeviceInfo,
+    saved_history: Option<&(Duration, Vec<SavedHistory>)>,
+    saved_favorites: &[String],
+) -> DeviceView {
+    // 单网卡间隔覆盖: 取第一个名称模糊匹配的 --device-interval
+    let lower = info.name.to_lowercase();
+    let interval_ms = args
+        .device_interval
+        .iter()
+        .find(|(pat, _)| lower.contains(&pat.to_lowercase()))
+        .map(|(_, ms)| *ms)
+        .unwrap_or(args.interval);
+    let mut engine = StatisticsEngine::new(interval_ms, args.average);
+    if let Some(n) = args.median {
+        engine.set_median_window(n as usize);
+    }
+    engine.set_avg_method(args.avg_method());
+    if let Some(secs) = args.window_total {
+        engine.set_window_total(secs);
+    }
+    if let Some(secs) = args.recent_max {
+        engine.set_recent_max(secs);
+    }
+    engine.set_spike_log(args.spikes as usize);
+    // --swap-direction 不带名称时作用于所有网卡
+    let swapped = args.swap_direction.as_ref().is_some_and(|names| {
+        names.is_empty() || names.iter().any(|n| lower.contains(&n.to_lowercase()))
+    });
+    // 接续上次运行的图形: 采样间隔相同才能对齐，停机期间的空档按采样数补 0
+    if let Some((age, saved)) = saved_history {
+        if let Some(h) = saved
+            .iter()
+            .find(|h| h.name == info.name && h.interval_ms == interval_ms)
+        {
+            let gap = (age.as_millis() / interval_ms.max(1) as u128) as usize;
+            engine.restore_history(&h.incoming, &h.outgoing, gap);
+        }
+    }
+    let favorite = saved_favorites.contains(&info.name)
+        || args.favorite.iter().any(|n| lower.contains(&n.to_lowercase()));
+    DeviceView {
+        info,
+        engine,
+        interval: Duration::from_millis(interval_ms),
+        last_sample: None,
+        idle_alerted: false,
+        missed: 0,
+        swapped,
+        favorite,
+    }
 }
 
 /// 应用主状态
 pub struct App {
     pub views: Vec<DeviceView>,
     pub current_idx: usize,
+    /// 主循环节拍 = 所有网卡采样间隔中的最小值
+    pub tick_rate: Duration,
+    /// 实际采样间隔的平滑值 (EMA)，用于检测采样是否跟不上节拍
+    pub sample_gap: Duration,
+    last_update: Option<Instant>,
     pub emoji: bool,
     pub unicode: bool,
+    /// 图形字符集 (--graph-chars，未指定时由 -U 决定)
+    pub graph_chars: GraphChars,
+    /// 图形空白格的背景字符 (--bg-char)，None 时使用字符集自带的空白字符
+    pub bg_char: Option<char>,
+    /// 图形平滑半径 (--display-smooth，0 = 关闭)
+    pub display_smooth: u8,
+    /// 历史压缩到图形宽度的方式 (--downsample，None = 每列一个样本)
+    pub downsample: Option<Downsample>,
     pub unit: Unit,
     pub bar_style: BarStyle,
     pub in_color: ratatui::style::Color,
     pub out_color: ratatui::style::Color,
     pub fixed_max: Option<f64>,
     pub no_graph: bool,
+    /// 用占 --max 百分比的横向进度条代替图形
+    pub gauge: bool,
+    /// 收发面板共用同一缩放上限
+    pub shared_scale: bool,
+    /// 面板内的速率统一按刻度选单位前缀 (--fixed-unit)
+    pub fixed_unit: bool,
+    /// --compare: 右栏对比的网卡下标 (左栏为当前网卡)
+    pub compare_idx: Option<usize>,
+    /// 总览模式 (m 键): 网格中同时显示所有网卡的当前速率与迷你图
+    pub overview: bool,
+    /// --eta-target 目标字节数
+    pub eta_target: Option<f64>,
+    /// 滚动窗口总量的窗口长度 (--window-total，秒)
+    pub window_total: Option<u64>,
+    /// 近期峰值的窗口长度 (--recent-max，秒)
+    pub recent_max: Option<u64>,
+    /// 收方向面板的高度占比 (%)
+    pub split: u16,
+    /// 噪声基线 (bytes/s)，绘图前减去
+    pub noise_floor: f64,
+    /// 统计数值也减去噪声基线
+    pub noise_floor_stats: bool,
+    /// 图形纵轴的裁剪范围 (--crop-min / --crop-max，bytes/s)
+    pub crop_min: Option<f64>,
+    pub crop_max: Option<f64>,
+    /// Ttl 显示精确字节数 (t 键切换)
+    pub exact_total: bool,
+    /// 精确字节数的千位分隔符 (--thousands-sep，None = 不分组)
+    pub thousands_sep: Option<char>,
+    /// 标题栏显示的地址族 (a 键切换)
+    pub addr_family: AddrFamily,
+    /// 状态栏字段 (--status)，为空时不显示状态栏
+    pub status_fields: Vec<StatusField>,
+    /// 程序启动时间 (状态栏运行时长)
+    pub started: Instant,
+    /// 图形冻结 (f 键切换)，统计数值仍实时更新
+    pub graph_frozen: bool,
+    /// 显示的数据是否为实时数据，由 [`App::update`] 设置；为 false 时图形变暗并标出原因:
+    ///
+    /// - 图形冻结 (f 键)
+    /// - 数据源不再前进: 回放结束后停在最后一帧、远程 / 外部命令不再产生新样本
+    /// - 数据源一个快照都没有返回
+    ///
+    /// 单个网卡连续采集不到数据 ([`DeviceView::no_data`]) 时只影响该网卡的面板。
+    pub data_fresh: bool,
+    /// 上一轮快照中最新的时间戳，用于判断数据源是否仍在前进
+    last_elapsed: Option<f64>,
+    /// 图形时间窗口的缩放级别 (+ / - / 0 键)，0 为每列一个样本
+    pub zoom: i8,
+    /// 在速率图下方显示本次运行的累计字节迷你图
+    pub cumulative: bool,
+    /// 旧的图形列逐渐变暗，突出最新数据
+    pub fade: bool,
+    /// 在图形最新一列上方标注当前数值
+    pub live_value: bool,
+    /// 在图形顶行标出刻度上限
+    pub show_ceiling: bool,
+    /// 图形背景每隔多少秒 (样本时刻) 画一条竖线 (--time-grid)
+    pub time_grid: Option<u64>,
+    /// 按键表 (默认按键加上配置文件的重映射)
+    keymap: Vec<(KeyCode, KeyAction)>,
+    /// 在图形右上角显示图例 (l 键切换)
+    pub legend: bool,
+    /// 显示当前速率的计算输入浮层 (--verbose / v 键)
+    pub rate_debug: bool,
+    /// 显示峰值记录浮层 (s 键)
+    pub spike_log: bool,
+    /// 在帮助栏显示下次采样倒计时 (--countdown)
+    pub countdown: bool,
+    /// q / Esc 先询问再退出 (--confirm-quit)
+    pub confirm_quit: bool,
+    /// 正在显示 "Quit? (y/n)" 提示，等待确认
+    pub confirming_quit: bool,
+    /// 距下次采样的时间，每帧绘制前由主循环更新
+    pub next_sample_in: Duration,
+    /// 按高度渐变着色 (绿 → 黄 → 红)
+    pub heat: bool,
+    /// 在头部右侧显示所有网卡的总吞吐
+    pub show_total: bool,
+    /// 在面板标签末尾显示当前速率 (--no-label-rate 关闭)
+    pub label_rate: bool,
+    /// 静默告警阈值 (--idle-alert)
+    pub idle_alert: Option<Duration>,
+    /// 收方向速率告警阈值 (--alert-rx，bytes/s)
+    pub alert_rx: Option<f64>,
+    /// 发方向速率告警阈值 (--alert-tx，bytes/s)
+    pub alert_tx: Option<f64>,
+    /// 当前网卡是否正处于超阈值状态 (每次超出只响铃一次)
+    threshold_alerted: bool,
+    /// 本次运行中是否触发过告警 (决定 --duration 的退出码)
+    alert_fired: bool,
+    /// 所有网卡当前收速率之和 (bytes/s)
+    pub total_in: f64,
+    /// 所有网卡当前发速率之和 (bytes/s)
+    pub total_out: f64,
     pub loopback_mode: LoopbackMode,
     pub loopback_info: Option<String>,
+    /// --pcap 协议拆分 (启动成功时)
+    pub proto: Option<ProtoView>,
+    /// --pcap 启动失败的原因
+    pub proto_error: Option<String>,
+    /// 帮助栏短提示及其产生时刻
+    notice: Option<(String, Instant)>,
+    /// `i` 调试信息浮层的内容 (关闭时为 None)
+    pub debug_overlay: Option<String>,
+    /// 数据源说明，显示在调试信息中
+    pub source_label: String,
+    /// 调试信息浮层的滚动行数
+    pub debug_scroll: u16,
+    /// --top 显示的进程数
+    pub top_n: usize,
+    /// 最近一次采样的进程列表 (按 process_sort 排序；--top 面板只显示前 top_n 个)
+    pub top_rows: Vec<ProcessRate>,
+    /// 进程页 (p 键): 用全部进程的带宽排行代替收发面板
+    pub process_tab: bool,
+    /// 进程列表的排序方式 (o 键切换)
+    pub process_sort: ProcessSort,
+    /// --log-alerts 无法打开系统日志的原因
+    pub alert_log_error: Option<String>,
     loopback_counters: Option<LoopbackCounters>,
-    collector: Collector,
+    /// --socket 指标广播
+    metrics_socket: Option<MetricsSocket>,
+    /// --record 录制器
+    recorder: Option<Recorder>,
+    /// --log-alerts 系统日志
+    alert_log: Option<AlertLog>,
+    /// --top 按进程统计
+    process_monitor: Option<ProcessMonitor>,
+    /// 读写按网卡名记录的状态 (上次查看的网卡、速率历史、收藏)，仅限本机数据源
+    persist_state: bool,
+    /// 流量数据源: 本机网卡，或 --remote 时的远程主机
+    source: Box<dyn TrafficSource>,
+    /// 以接口索引为键的设备的名称 (含未建视图的设备)，供录制与回环覆盖按名称查找
+    device_names: HashMap<u32, String>,
+    /// 启动时没有匹配到的 -d 名称 (已转小写)；热插拔扫描发现匹配的新网卡时自动选中一次，
+    /// 用户手动切换过网卡后不再自动跳转
+    pending_device: Option<String>,
+    /// 上次热插拔扫描的时间
+    last_rescan: Instant,
 }
 
 impl App {
-    fn new(args: &Args) -> Self {
-        let collector = Collector::new();
-        let devices = collector.devices();
+    fn new(args: &Args, source: Box<dyn TrafficSource>) -> Self {
+        // 快照以接口索引 (没有时以名称) 为键，键相同的设备合并为一个视图，避免共用 / 覆盖同一条快照
+        let devices = collector::merge_duplicate_devices(source.devices());
+        let device_names = devices
+            .iter()
+            .filter_map(|d| Some((d.index?, d.name.clone())))
+            .collect();
 
-        let views: Vec<DeviceView> = devices
+        // 远程 / 命令 / 回放 / 其他网络命名空间的网卡与本机同名时不是同一块网卡，不读写本机状态
+        let persist_state = args.remote.is_none()
+            && args.source_cmd.is_none()
+            && args.replay.is_none()
+            && args.netns.is_none();
+        let saved_history = if persist_state && !args.no_restore_history {
+            state::load_history()
+        } else {
+            None
+        };
+        let saved_favorites = if persist_state { state::load_favorites() } else { Vec::new() };
+
+        let mut views: Vec<DeviceView> = devices
             .into_iter()
-            .map(|info| DeviceView {
-                info,
-                engine: StatisticsEngine::new(args.interval, args.average),
-            })
+            .map(|info| new_device_view(args, info, saved_history.as_ref(), &saved_favorites))
             .collect();
 
         // 如果指定了默认设备，定位到对应索引
         let mut current_idx = 0;
-        if let Some(ref name) = args.device {
-            let lower = name.to_lowercase();
-            if let Some(idx) = views
+        let mut pending_device = None;
+        let patterns: Vec<String> = args.device.iter().map(|d| d.to_lowercase()).collect();
+        if let [name] = patterns.as_slice() {
+            match views
                 .iter()
-                .position(|v| v.info.name.to_lowercase().contains(&lower))
+                .position(|v| v.info.name.to_lowercase().contains(name))
             {
-                current_idx = idx;
+                Some(idx) => current_idx = idx,
+                // 网卡可能稍后才出现 (如 VPN)，由热插拔扫描补选
+                None => pending_device = Some(name.clone()),
+            }
+        } else if patterns.len() > 1 {
+            // 多个 -d: 只保留匹配的网卡参与切换；全都匹配不到时保留全部，避免空界面
+            let matches = |v: &DeviceView| {
+                let lower = v.info.name.to_lowercase();
+                patterns.iter().any(|p| lower.contains(p))
+            };
+            if views.iter().any(matches) {
+                views.retain(matches);
+            }
+        } else if persist_state {
+            // 未指定 -d 时恢复上次查看的网卡；已不存在则保持第一个
+            if let Some(last) = state::load_last_device() {
+                if let Some(idx) = views.iter().position(|v| v.info.name == last) {
+                    current_idx = idx;
+                }
+            }
+        }
+        // --index 优先于名称匹配；找不到该索引时保留上面的选择，并在帮助栏提示
+        let mut notice = None;
+        if let Some(index) = args.index {
+            match views.iter().position(|v| v.info.index == Some(index)) {
+                Some(idx) => {
+                    current_idx = idx;
+                    pending_device = None;
+                }
+                None => notice = Some((format!("No interface with index {index}"), Instant::now())),
+            }
+        }
+
+        // --compare: 左栏为 A (作为当前网卡)，右栏为 B；B 优先取与 A 不同的网卡
+        let mut compare_idx = None;
+        if let [a, b] = args.compare.as_slice() {
+            let find = |pat: &str, skip: Option<usize>| {
+                let pat = pat.to_lowercase();
+                let matching = |i: &usize| views[*i].info.name.to_lowercase().contains(&pat);
+                (0..views.len())
+                    .filter(matching)
+                    .find(|&i| Some(i) != skip)
+                    .or_else(|| (0..views.len()).find(matching))
+            };
+            match find(a, None).zip(find(b, find(a, None))) {
+                Some((left, right)) => {
+                    current_idx = left;
+                    compare_idx = Some(right);
+                    pending_device = None;
+                }
+                None => notice = Some((format!("--compare: no device matches '{a}' or '{b}'"), Instant::now())),
             }
         }
 
+        let tick_rate = views
+            .iter()
+            .map(|v| v.interval)
+            .chain(std::iter::once(Duration::from_millis(args.interval)))
+            .min()
+            .unwrap_or(Duration::from_millis(args.interval));
+
         let loopback_mode = if args.npcap {
             LoopbackMode::Npcap
         } else if args.etw {
@@ -210,64 +1346,606 @@ impl App {
         Self {
             views,
             current_idx,
+            tick_rate,
+            sample_gap: tick_rate,
+            last_update: None,
             emoji: args.emoji,
             unicode: args.unicode,
-            unit: args.unit,
+            graph_chars: args.graph_chars.unwrap_or(if args.unicode {
+                GraphChars::BLOCK
+            } else {
+                GraphChars::ASCII
+            }),
+            bg_char: args.bg_char.map(|c| c.glyph(args.unicode)),
+            display_smooth: args.display_smooth.unwrap_or(0),
+            downsample: args.downsample.map(Into::into),
+            unit: args.unit.into(),
             bar_style: args.bar_style,
             in_color: args.in_color.unwrap_or(ratatui::style::Color::Rgb(0x00, 0xd7, 0xff)),
             out_color: args.out_color.unwrap_or(ratatui::style::Color::Rgb(0xff, 0xaf, 0x00)),
             fixed_max: args.max,
             no_graph: args.no_graph,
+            gauge: args.gauge,
+            shared_scale: args.shared_scale,
+            fixed_unit: args.fixed_unit,
+            compare_idx,
+            overview: false,
+            eta_target: args.eta_target,
+            window_total: args.window_total,
+            recent_max: args.recent_max,
+            split: args.split,
+            noise_floor: args.noise_floor.unwrap_or(0.0),
+            noise_floor_stats: args.noise_floor_stats,
+            crop_min: args.crop_min,
+            crop_max: args.crop_max,
+            exact_total: false,
+            thousands_sep: args.thousands_sep(),
+            addr_family: AddrFamily::Both,
+            status_fields: args.status.clone(),
+            started: Instant::now(),
+            graph_frozen: false,
+            data_fresh: true,
+            last_elapsed: None,
+            zoom: 0,
+            cumulative: args.cumulative,
+            fade: args.fade,
+            live_value: args.live_value,
+            show_ceiling: args.show_ceiling,
+            time_grid: args.time_grid,
+            keymap: args.keymap.clone(),
+            legend: args.legend,
+            rate_debug: args.verbose,
+            spike_log: false,
+            countdown: args.countdown,
+            confirm_quit: args.confirm_quit,
+            confirming_quit: false,
+            next_sample_in: Duration::ZERO,
+            heat: args.heat,
+            show_total: !args.no_total,
+            label_rate: !args.no_label_rate,
+            idle_alert: args.idle_alert.map(Duration::from_secs),
+            alert_rx: args.alert_rx,
+            alert_tx: args.alert_tx,
+            threshold_alerted: false,
+            alert_fired: false,
+            total_in: 0.0,
+            total_out: 0.0,
             loopback_mode,
             loopback_info: None,
+            proto: None,
+            proto_error: None,
+            notice,
+            debug_overlay: None,
+            source_label: source_label(args),
+            debug_scroll: 0,
+            top_n: args.top.map_or(0, usize::from),
+            top_rows: Vec::new(),
+            process_tab: false,
+            process_sort: ProcessSort::default(),
+            alert_log_error: None,
             loopback_counters: None,
-            collector,
+            metrics_socket: None,
+            recorder: None,
+            alert_log: None,
+            process_monitor: None,
+            persist_state,
+            source,
+            device_names,
+            pending_device,
+            last_rescan: Instant::now(),
+        }
+    }
+
+    /// 热插拔扫描: 为启动后新出现的网卡创建视图 (追加在末尾，不打乱现有索引)
+    ///
+    /// 多个 -d 时只加入匹配的网卡；新网卡匹配启动时没找到的 -d 名称时自动选中。
+    fn rescan_devices(&mut self, args: &Args) {
+        self.last_rescan = Instant::now();
+        self.source.refresh_devices();
+        let patterns: Vec<String> = args.device.iter().map(|d| d.to_lowercase()).collect();
+        let devices = collector::merge_duplicate_devices(self.source.devices());
+        for info in &devices {
+            if let Some(index) = info.index {
+                self.device_names.entry(index).or_insert_with(|| info.name.clone());
+            }
+        }
+        let added: Vec<DeviceInfo> = devices
+            .into_iter()
+            .filter(|info| !self.views.iter().any(|v| v.info.key() == info.key()))
+            .filter(|info| {
+                let lower = info.name.to_lowercase();
+                patterns.len() < 2 || patterns.iter().any(|p| lower.contains(p))
+            })
+            .collect();
+        if added.is_empty() {
+            return;
+        }
+        let saved_favorites = if self.persist_state { state::load_favorites() } else { Vec::new() };
+        for info in added {
+            let name = info.name.clone();
+            let view = new_device_view(args, info, None, &saved_favorites);
+            // 新网卡的 --device-interval 可能更短，主循环按所有网卡中最短的间隔采集
+            self.tick_rate = self.tick_rate.min(view.interval);
+            self.views.push(view);
+            let wanted = self
+                .pending_device
+                .as_ref()
+                .is_some_and(|p| name.to_lowercase().contains(p));
+            if wanted {
+                self.pending_device = None;
+                self.current_idx = self.views.len() - 1;
+                self.notice = Some((format!("{name} appeared, switched to it"), Instant::now()));
+            }
         }
     }
 
+    /// 记住当前网卡，下次启动时恢复
+    fn save_last_device(&self) {
+        if !self.persist_state {
+            return;
+        }
+        if let Some(view) = self.current_view() {
+            state::save_last_device(&view.info.name);
+        }
+    }
+
+    /// 保存各网卡的速率历史，下次启动时恢复图形
+    fn save_history(&self) {
+        if !self.persist_state {
+            return;
+        }
+        let saved: Vec<state::SavedHistory> = self
+            .views
+            .iter()
+            .filter(|v| !v.engine.incoming_history.is_empty())
+            .map(|v| state::SavedHistory {
+                name: v.info.name.clone(),
+                interval_ms: v.interval.as_millis() as u64,
+                incoming: v.engine.incoming_history.iter().copied().collect(),
+                outgoing: v.engine.outgoing_history.iter().copied().collect(),
+            })
+            .collect();
+        state::save_history(&saved);
+    }
+
+    /// 数据源异常提示 (如远程连接断开重连中)
+    pub fn source_status(&self) -> Option<String> {
+        self.source.status()
+    }
+
+    /// 图形底部对应的速率: 噪声基线与 --crop-min 中较大者，绘制前从每个值中减去
+    pub fn graph_floor(&self) -> f64 {
+        self.noise_floor.max(self.crop_min.unwrap_or(0.0))
+    }
+
     pub fn current_view(&self) -> Option<&DeviceView> {
         self.views.get(self.current_idx)
     }
 
+    /// --compare 右栏的网卡
+    pub fn compare_view(&self) -> Option<&DeviceView> {
+        self.compare_idx.and_then(|idx| self.views.get(idx))
+    }
+
+    /// 交换 --compare 的左右两栏 (x 键)
+    fn swap_compare(&mut self) {
+        if let Some(idx) = self.compare_idx.as_mut() {
+            std::mem::swap(idx, &mut self.current_idx);
+        }
+    }
+
+    /// 帮助栏中仍在显示期内的短提示
+    pub fn notice(&self) -> Option<&str> {
+        self.notice
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < NOTICE_DURATION)
+            .map(|(text, _)| text.as_str())
+    }
+
+    /// 当前网卡统计的纯文本摘要 (c 键复制)
+    fn stats_summary(&self) -> Option<String> {
+        self.current_view().map(|view| self.device_summary(view))
+    }
+
+    /// 所有网卡统计的纯文本摘要 (--summary 退出时打印)
+    fn exit_summary(&self) -> String {
+        self.views.iter().map(|view| self.device_summary(view)).collect()
+    }
+
+    /// 单个网卡的摘要: 名称 + 收 / 发各一行
+    fn device_summary(&self, view: &DeviceView) -> String {
+        let line = |label: &str, st: &TrafficStats| {
+            format!(
+                "{label}: Curr {} | Avg {} | Min {} | Max {} | Ttl {}",
+                stats::format_speed_unit(st.current, self.unit),
+                stats::format_speed_unit(st.average, self.unit),
+                stats::format_speed_unit(if st.minimum.is_finite() { st.minimum } else { 0.0 }, self.unit),
+                stats::format_speed_unit(st.maximum, self.unit),
+                stats::format_bytes(st.total, self.unit),
+            )
+        };
+        // 峰值记录: "速率 (+时刻)"，按速率从高到低
+        let spike_line = |label: &str, spikes: &[stats::Spike]| {
+            if spikes.is_empty() {
+                return String::new();
+            }
+            let items: Vec<String> = spikes
+                .iter()
+                .map(|s| {
+                    format!(
+                        "{} (+{})",
+                        stats::format_speed_unit(s.speed, self.unit),
+                        stats::format_hms(s.at_secs as u64)
+                    )
+                })
+                .collect();
+            format!("{label} spikes: {}\n", items.join(", "))
+        };
+        let (in_spikes, out_spikes) = view.engine.spikes();
+        format!(
+            "{}\n{}\n{}\n{}{}",
+            view.info.name,
+            line("In ", &view.engine.incoming),
+            line("Out", &view.engine.outgoing),
+            spike_line("In ", in_spikes),
+            spike_line("Out", out_spikes),
+        )
+    }
+
+    /// 复制当前网卡统计到剪贴板，结果显示在帮助栏
+    fn copy_stats(&mut self) {
+        let Some(summary) = self.stats_summary() else {
+            return;
+        };
+        let text = match clipboard::copy(&summary) {
+            Ok(_) => "Copied stats to clipboard".to_string(),
+            Err(e) => format!("Copy failed: {e}"),
+        };
+        self.notice = Some((text, Instant::now()));
+    }
+
+    /// 把当前网卡保留的速率历史导出为 CSV (e 键)，结果显示在帮助栏
+    fn export_history(&mut self) {
+        let Some(view) = self.current_view() else {
+            return;
+        };
+        let samples = view.engine.incoming_history.len();
+        let text = match export::export_history(&view.info.name, &view.engine) {
+            Ok(path) => format!("Saved {samples} samples to {}", path.display()),
+            Err(e) => format!("Export failed: {e}"),
+        };
+        self.notice = Some((text, Instant::now()));
+    }
+
+    /// 采样是否明显落后于设定间隔 (实际间隔超过 1.5 倍)
+    pub fn sampling_behind(&self) -> bool {
+        self.sample_gap > self.tick_rate.mul_f64(1.5)
+    }
+
     fn update(&mut self) {
-        let mut snapshots = self.collector.collect();
+        // 记录实际采样间隔，平滑后用于"采样落后"提示
+        let now = Instant::now();
+        if let Some(prev) = self.last_update {
+            let gap = now.duration_since(prev);
+            self.sample_gap = self.sample_gap.mul_f64(0.8) + gap.mul_f64(0.2);
+        }
+        self.last_update = Some(now);
+
+        let mut snapshots = self.source.collect();
+
+        // 快照时间戳没有前进 (或没有快照) 说明数据源已停止产生新数据
+        let newest = snapshots.values().map(|s| s.elapsed_secs).reduce(f64::max);
+        let advanced = newest.is_some_and(|t| self.last_elapsed.is_none_or(|prev| t > prev));
+        if newest.is_some() {
+            self.last_elapsed = newest;
+        }
+        self.data_fresh = advanced && !self.graph_frozen;
 
         // 如果启用了回环捕获，用实时计数器覆盖 loopback 的假数据
         if let Some(ref counters) = self.loopback_counters {
-            let elapsed = self.collector.elapsed_secs();
-            for (name, snap) in snapshots.iter_mut() {
+            for (key, snap) in snapshots.iter_mut() {
+                let name = key_name(&self.device_names, key).unwrap_or_default();
                 if name.to_lowercase().contains("loopback") {
                     snap.bytes_recv = counters.get_recv();
                     snap.bytes_sent = counters.get_sent();
-                    snap.elapsed_secs = elapsed;
                 }
             }
         }
 
+        // 录制喂给统计引擎的原始计数器；写入失败时停止录制，不影响监控
+        if let Some(ref mut rec) = self.recorder {
+            // 录制文件按设备名记录
+            let named: HashMap<String, Snapshot> = snapshots
+                .iter()
+                .filter_map(|(key, snap)| Some((key_name(&self.device_names, key)?.to_string(), snap.clone())))
+                .collect();
+            if rec.write_frame(&named).is_err() {
+                self.recorder = None;
+            }
+        }
+
+        // 每个网卡按自己的间隔采样；允许半个节拍的误差，避免因调度抖动错过一拍
+        let slack = self.tick_rate / 2;
         for view in &mut self.views {
-            if let Some(snap) = snapshots.get(&view.info.name) {
-                view.engine.update(snap.clone());
+            let due = view
+                .last_sample
+                .is_none_or(|t| now.duration_since(t) + slack >= view.interval);
+            if !due {
+                continue;
+            }
+            // 链路状态可能随时变化 (拔网线 / ip link set down)，每次采样时刷新
+            view.info.link_up = self.source.link_up(&view.info.name);
+            view.last_sample = Some(now);
+            match snapshots.get(&view.info.key()) {
+                Some(snap) => {
+                    let mut snap = snap.clone();
+                    if view.swapped {
+                        std::mem::swap(&mut snap.bytes_recv, &mut snap.bytes_sent);
+                    }
+                    view.engine.update(snap);
+                    view.missed = 0;
+                }
+                None => {
+                    view.missed = view.missed.saturating_add(1);
+                    // 不再显示停留在最后一次的速率，避免看起来像卡住
+                    if view.no_data() {
+                        view.engine.clear_current();
+                    }
+                }
+            }
+        }
+
+        if let Some(ref mut proto) = self.proto {
+            proto.update();
+        }
+
+        if let Some(ref mut monitor) = self.process_monitor {
+            self.top_rows = monitor.sample();
+            self.process_sort.sort(&mut self.top_rows);
+        }
+
+        // 静默告警: 每轮静默只响铃一次，恢复流量后重新计数
+        let mut beep = false;
+        for view in &mut self.views {
+            let idle = view.is_idle(self.idle_alert);
+            if idle != view.idle_alerted {
+                // --log-alerts: 触发 / 解除的边沿各记一条
+                if let Some(ref mut log) = self.alert_log {
+                    let (level, state) = if idle {
+                        (AlertLevel::Warning, "fired")
+                    } else {
+                        (AlertLevel::Info, "cleared")
+                    };
+                    log.log(
+                        level,
+                        &format!(
+                            "alert=idle state={state} device={:?} idle_secs={:.0}",
+                            view.info.name,
+                            view.engine.idle_secs()
+                        ),
+                    );
+                }
+            }
+            beep |= idle && !view.idle_alerted;
+            view.idle_alerted = idle;
+        }
+        self.alert_fired |= beep;
+
+        // 速率阈值告警 (--alert-rx / --alert-tx): 只看当前显示的网卡
+        let exceeded = self.threshold_exceeded();
+        if exceeded.is_some() != self.threshold_alerted {
+            // --log-alerts: 与静默告警一样记录触发 / 解除的边沿
+            let device = self.current_view().map(|v| v.info.name.clone()).unwrap_or_default();
+            if let Some(ref mut log) = self.alert_log {
+                let msg = match exceeded {
+                    Some((dir, current, limit)) => format!(
+                        "alert=threshold state=fired device={device:?} dir={dir} bytes_per_sec={current:.0} limit={limit:.0}"
+                    ),
+                    None => format!("alert=threshold state=cleared device={device:?}"),
+                };
+                let level = if exceeded.is_some() { AlertLevel::Warning } else { AlertLevel::Info };
+                log.log(level, &msg);
             }
         }
+        if exceeded.is_some() && !self.threshold_alerted {
+            beep = true;
+            self.alert_fired = true;
+        }
+        self.threshold_alerted = exceeded.is_some();
+        if beep {
+            ring_bell();
+        }
+
+        if let Some(ref mut sock) = self.metrics_socket {
+            let line = socket::metrics_line(
+                self.views.iter().map(|v| (v.info.name.as_str(), &v.engine)),
+            );
+            sock.broadcast(&line);
+        }
+
+        self.total_in = self.views.iter().map(|v| v.engine.incoming.current).sum();
+        self.total_out = self.views.iter().map(|v| v.engine.outgoing.current).sum();
+    }
+
+    /// 当前网卡超出的速率阈值: (方向, 当前速率, 阈值)，未超出时为 None
+    pub fn threshold_exceeded(&self) -> Option<(&'static str, f64, f64)> {
+        let engine = &self.current_view()?.engine;
+        let check = |dir, current: f64, limit: Option<f64>| {
+            limit.filter(|&l| current > l).map(|l| (dir, current, l))
+        };
+        check("in", engine.incoming.current, self.alert_rx)
+            .or_else(|| check("out", engine.outgoing.current, self.alert_tx))
+    }
+
+    /// 供脚本判断的退出码 (--duration 结束时):
+    /// 当前网卡链路断开或无数据为 [`EXIT_NO_DATA`]，否则触发过告警为 [`EXIT_ALERT`]，正常为 0
+    fn exit_code(&self) -> i32 {
+        let down = self
+            .current_view()
+            .is_none_or(|v| v.no_data() || v.info.link_up == Some(false));
+        if down {
+            EXIT_NO_DATA
+        } else if self.alert_fired {
+            EXIT_ALERT
+        } else {
+            0
+        }
+    }
+
+    /// 打开调试信息浮层 (内容与 --debug-info 相同，打开时从当前数据源读取一次)
+    fn open_debug_overlay(&mut self) {
+        self.debug_overlay = Some(debug_info_text(self.source.as_ref(), &self.source_label).trim().to_string());
+        self.debug_scroll = 0;
+    }
+
+    /// 滚动调试信息浮层，不超过最后一行
+    fn scroll_debug_overlay(&mut self, code: KeyCode) {
+        let lines = self
+            .debug_overlay
+            .as_ref()
+            .map_or(0, |t| t.lines().count() as u16);
+        let max = lines.saturating_sub(1);
+        self.debug_scroll = match code {
+            KeyCode::Up => self.debug_scroll.saturating_sub(1),
+            KeyCode::Down => self.debug_scroll.saturating_add(1),
+            KeyCode::PageUp => self.debug_scroll.saturating_sub(DEBUG_PAGE_LINES),
+            KeyCode::PageDown => self.debug_scroll.saturating_add(DEBUG_PAGE_LINES),
+            _ => self.debug_scroll,
+        }
+        .min(max);
+    }
+
+    /// 收藏 / 取消收藏当前网卡，立即写入状态文件
+    fn toggle_favorite(&mut self) {
+        let Some(view) = self.views.get_mut(self.current_idx) else {
+            return;
+        };
+        view.favorite = !view.favorite;
+        if self.persist_state {
+            // 在已保存的列表上增删，本次不在场的网卡 (如未连接的 VPN) 保留收藏
+            let mut names = state::load_favorites();
+            names.retain(|n| *n != view.info.name);
+            if view.favorite {
+                names.push(view.info.name.clone());
+            }
+            state::save_favorites(&names);
+        }
+    }
+
+    /// 切换到下一个收藏的网卡 (只有当前一个收藏时保持不动)
+    fn next_favorite(&mut self) {
+        self.pending_device = None;
+        let len = self.views.len();
+        let next = (1..=len)
+            .map(|step| (self.current_idx + step) % len)
+            .find(|&idx| self.views[idx].favorite);
+        match next {
+            Some(idx) => self.current_idx = idx,
+            None => self.notice = Some(("No favorites (press * to star a device)".to_string(), Instant::now())),
+        }
+    }
+
+    /// 按键表中按键对应的动作
+    fn key_action(&self, key: &KeyEvent) -> Option<KeyAction> {
+        self.keymap.iter().find(|(k, _)| *k == key.code).map(|&(_, action)| action)
+    }
+
+    /// 打开 / 关闭进程页；未使用 --top 时在首次打开时启动按进程统计，不支持时在帮助栏提示
+    fn toggle_process_tab(&mut self) {
+        if !self.process_tab && self.process_monitor.is_none() {
+            match ProcessMonitor::new() {
+                Ok(monitor) => self.process_monitor = Some(monitor),
+                Err(e) => {
+                    let reason = e.lines().next().unwrap_or_default().to_string();
+                    self.notice = Some((reason, Instant::now()));
+                    return;
+                }
+            }
+        }
+        self.process_tab = !self.process_tab;
+    }
+
+    /// 切换进程列表的排序方式，立即对当前列表重新排序
+    fn cycle_process_sort(&mut self) {
+        self.process_sort = self.process_sort.next();
+        self.process_sort.sort(&mut self.top_rows);
+    }
+
+    /// 冻结 / 恢复所有网卡的图形
+    fn toggle_graph_frozen(&mut self) {
+        self.graph_frozen = !self.graph_frozen;
+        // 冻结立即生效；恢复后由下一次 update 判断数据源是否仍在前进
+        if self.graph_frozen {
+            self.data_fresh = false;
+        }
+        for view in &mut self.views {
+            view.engine.set_history_frozen(self.graph_frozen);
+        }
     }
 
     fn next_device(&mut self) {
+        self.pending_device = None;
         if !self.views.is_empty() {
             self.current_idx = (self.current_idx + 1) % self.views.len();
         }
     }
 
     fn prev_device(&mut self) {
+        self.pending_device = None;
         if !self.views.is_empty() {
             self.current_idx = (self.current_idx + self.views.len() - 1) % self.views.len();
         }
     }
 }
 
+/// --duration 结束时的退出码: 运行期间触发过告警 (--alert-rx / --alert-tx / --idle-alert)
+const EXIT_ALERT: i32 = 1;
+/// --duration 结束时的退出码: 当前网卡链路断开或采集不到数据
+const EXIT_NO_DATA: i32 = 2;
+
+/// run 结束后交给 main 处理的结果
+struct RunOutcome {
+    /// --summary 的统计文本
+    summary: Option<String>,
+    /// --report-json 的 JSON 报告
+    report: Option<String>,
+    /// 进程退出码，仅 --duration 时可能非 0
+    exit_code: i32,
+}
+
+/// 向终端输出 BEL 字符 (响铃)
+fn ring_bell() {
+    use std::io::Write;
+    let mut out = io::stdout();
+    let _ = out.write_all(b"\x07");
+    let _ = out.flush();
+}
+
 // ─── 主循环 ────────────────────────────────────────────────
 
-fn run(terminal: &mut ratatui::DefaultTerminal, args: Args) -> io::Result<()> {
-    let mut app = App::new(&args);
+/// 判断一个按键事件是否应当触发动作（跨平台归一化）
+///
+/// - Windows: crossterm 对每次按键产生 Press + Release，只能响应其中一次，
+///   否则一次按键会切换两次网卡；长按产生的 Repeat 照常响应。
+/// - Linux/macOS: 未开启 kitty 键盘增强协议时终端只上报 Press，
+///   个别终端的事件类型不可靠，因此只排除明确的 Release，不丢弃任何按键。
+fn is_actionable_key(key: &KeyEvent) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        matches!(key.kind, KeyEventKind::Press | KeyEventKind::Repeat)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        key.kind != KeyEventKind::Release
+    }
+}
+
+fn run(
+    terminal: &mut ratatui::DefaultTerminal,
+    args: Args,
+    source: Box<dyn TrafficSource>,
+) -> io::Result<RunOutcome> {
+    let mut app = App::new(&args, source);
 
     // 启动回环捕获 (如果指定了 --npcap 或 --etw)
     if app.loopback_mode != LoopbackMode::None {
@@ -291,39 +1969,161 @@ fn run(terminal: &mut ratatui::DefaultTerminal, args: Args) -> io::Result<()> {
         }
     }
 
-    let tick_rate = Duration::from_millis(args.interval);
+    // 开始录制 (--record)
+    if let Some(ref path) = args.record {
+        match Recorder::create(path) {
+            Ok(rec) => app.recorder = Some(rec),
+            Err(e) => {
+                ratatui::restore();
+                eprintln!("Error: Failed to create recording {}: {e}", path.display());
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // 打开指标套接字 (--socket)
+    if let Some(ref path) = args.socket {
+        match MetricsSocket::bind(path) {
+            Ok(sock) => app.metrics_socket = Some(sock),
+            Err(e) => {
+                ratatui::restore();
+                eprintln!("Error: Failed to open socket {}: {e}", path.display());
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // 按进程统计 (--top)，当前平台不支持时报错退出
+    if args.top.is_some() {
+        match ProcessMonitor::new() {
+            Ok(monitor) => app.process_monitor = Some(monitor),
+            Err(e) => {
+                ratatui::restore();
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // 打开系统日志 (--log-alerts)，失败时在头部提示并继续运行
+    if args.log_alerts {
+        match AlertLog::open() {
+            Ok(log) => app.alert_log = Some(log),
+            Err(e) => app.alert_log_error = Some(e.to_string()),
+        }
+    }
+
+    // 启动协议拆分抓包 (--pcap)，失败时在头部提示并继续运行
+    if args.pcap {
+        if let Some(info) = app.current_view().map(|v| v.info.clone()) {
+            match proto::start(&info, args.filter.clone()) {
+                Ok(source) => app.proto = Some(ProtoView::new(source, &args)),
+                Err(e) => app.proto_error = Some(e),
+            }
+        }
+    }
+
     let mut last_tick = Instant::now();
+    // 绘制间隔: --fps 时独立于采集间隔 (只重绘，不额外采集)，否则每次采集后重绘；
+    // --countdown 需要在两次采集之间刷新倒计时，至少每 COUNTDOWN_FRAME 重绘一次
+    let frame_time = |tick_rate: Duration| match args.fps {
+        Some(fps) => Duration::from_secs_f64(1.0 / fps as f64),
+        None if args.countdown => tick_rate.min(COUNTDOWN_FRAME),
+        None => tick_rate,
+    };
+
+    // --duration: 到点后自动退出
+    let deadline = args.duration.map(|secs| Instant::now() + Duration::from_secs(secs));
 
     // 初始采集
     app.update();
 
     loop {
+        // 热插拔扫描加入的网卡可能缩短采集间隔，每轮重新读取
+        let tick_rate = app.tick_rate;
+        app.next_sample_in = tick_rate.saturating_sub(last_tick.elapsed());
         terminal.draw(|frame| ui::draw(frame, &app))?;
 
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
-            .unwrap_or_default();
+            .unwrap_or_default()
+            .min(frame_time(tick_rate));
+        // 不要等过 --duration 的截止时间
+        let timeout = deadline.map_or(timeout, |d| {
+            timeout.min(d.saturating_duration_since(Instant::now()))
+        });
 
         if crossterm::event::poll(timeout)? {
             if let Event::Key(key) = event::read()? {
-                // Windows 下 crossterm 会产生 Press + Release，只处理 Press
-                if key.kind == KeyEventKind::Press {
+                if is_actionable_key(&key) {
+                    let action = app.key_action(&key);
                     match key.code {
-                        KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => {
-                            return Ok(());
+                        // --confirm-quit 提示中: y 退出，其他键取消 (Ctrl-C 照常直接退出)
+                        code if app.confirming_quit && !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.confirming_quit = false;
+                            if matches!(code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                                break;
+                            }
+                        }
+                        // 调试信息浮层打开时: Esc / 信息键关闭，方向键滚动
+                        KeyCode::Esc if app.debug_overlay.is_some() => {
+                            app.debug_overlay = None;
+                        }
+                        _ if action == Some(KeyAction::Info) && app.debug_overlay.is_some() => {
+                            app.debug_overlay = None;
+                        }
+                        KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown
+                            if app.debug_overlay.is_some() =>
+                        {
+                            app.scroll_debug_overlay(key.code);
                         }
                         KeyCode::Char('c')
                             if key.modifiers.contains(KeyModifiers::CONTROL) =>
                         {
-                            return Ok(());
-                        }
-                        KeyCode::Right | KeyCode::Down | KeyCode::Tab | KeyCode::Enter => {
-                            app.next_device();
+                            break;
                         }
-                        KeyCode::Left | KeyCode::Up => {
-                            app.prev_device();
+                        // 总览中打开选中的网卡；其他视图预留给 "确认 / 放大"
+                        KeyCode::Enter => {
+                            app.overview = false;
                         }
-                        _ => {}
+                        _ => match action {
+                            Some(KeyAction::Quit) => {
+                                if !app.confirm_quit {
+                                    break;
+                                }
+                                app.confirming_quit = true;
+                            }
+                            Some(KeyAction::Copy) => app.copy_stats(),
+                            Some(KeyAction::Export) => app.export_history(),
+                            Some(KeyAction::NextDevice) => app.next_device(),
+                            Some(KeyAction::PrevDevice) => app.prev_device(),
+                            Some(KeyAction::Overview) => app.overview = !app.overview,
+                            Some(KeyAction::Processes) => app.toggle_process_tab(),
+                            Some(KeyAction::Sort) => app.cycle_process_sort(),
+                            Some(KeyAction::Unit) => app.unit = app.unit.next(),
+                            Some(KeyAction::Prefix) => {
+                                app.unit = app.unit.next_prefix();
+                                app.notice = Some((format!("Units: {}", app.unit.label()), Instant::now()));
+                            }
+                            Some(KeyAction::Freeze) => app.toggle_graph_frozen(),
+                            Some(KeyAction::Address) => app.addr_family = app.addr_family.next(),
+                            Some(KeyAction::Total) => app.exact_total = !app.exact_total,
+                            Some(KeyAction::Info) => app.open_debug_overlay(),
+                            Some(KeyAction::Legend) => app.legend = !app.legend,
+                            Some(KeyAction::Rates) => app.rate_debug = !app.rate_debug,
+                            Some(KeyAction::Spikes) => app.spike_log = !app.spike_log,
+                            Some(KeyAction::Star) => app.toggle_favorite(),
+                            Some(KeyAction::NextStar) => app.next_favorite(),
+                            Some(KeyAction::Shrink) => {
+                                app.split = app.split.saturating_sub(SPLIT_STEP).max(SPLIT_MIN);
+                            }
+                            Some(KeyAction::Grow) => app.split = (app.split + SPLIT_STEP).min(SPLIT_MAX),
+                            Some(KeyAction::ZoomIn) => app.zoom = (app.zoom + 1).min(ZOOM_MAX),
+                            Some(KeyAction::ZoomOut) => app.zoom = (app.zoom - 1).max(ZOOM_MIN),
+                            Some(KeyAction::ZoomReset) => app.zoom = 0,
+                            Some(KeyAction::Swap) => app.swap_compare(),
+                            None => {}
+                        },
                     }
                 }
             }
@@ -333,7 +2133,30 @@ fn run(terminal: &mut ratatui::DefaultTerminal, args: Args) -> io::Result<()> {
             app.update();
             last_tick = Instant::now();
         }
+        if app.last_rescan.elapsed() >= DEVICE_RESCAN_INTERVAL {
+            app.rescan_devices(&args);
+        }
+
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            break;
+        }
+        // 后台线程 panic 后终端已恢复，不能再绘制
+        if PANICKED.load(Ordering::SeqCst) {
+            break;
+        }
     }
+
+    app.save_last_device();
+    app.save_history();
+    Ok(RunOutcome {
+        summary: args.summary.then(|| app.exit_summary()),
+        report: args.report_json.is_some().then(|| {
+            let devices = app.views.iter().map(|v| (v.info.name.as_str(), &v.engine));
+            export::report_json(devices, app.started.elapsed().as_secs_f64())
+        }),
+        // 交互退出 (q) 总是 0，只有 --duration 的脚本化运行才反映告警状态
+        exit_code: if args.duration.is_some() { app.exit_code() } else { 0 },
+    })
 }
 
 // ─── 入口 ──────────────────────────────────────────────────
@@ -357,16 +2180,78 @@ nload-like TUI tool for Windows/Linux/macOS
 ⚙️  Options:
   -t, --interval <MS>       ⏱️  Refresh interval in milliseconds [default: 500]
   -a, --average <SECS>      📊 Average window in seconds [default: 300]
-  -d, --device <NAME>       🖧  Default device name (partial match)
+  -d, --device <NAME>       🖧  Default device name (partial match, a,b pins a subset)
+      --index <N>            🔢 Default device by OS interface index (see --debug-info)
+      --device-interval <NAME=MS>  ⏲️  Per-device refresh interval (repeatable)
+      --swap-direction [NAME]  🔁 Swap in/out counters (all devices, or matching NAMEs)
+      --favorite <NAME>      ⭐ Star devices; g jumps between starred ones
       --debug-info           🔍 Print debug info about network interfaces and exit
   -e, --emoji                😀 Enable emoji decorations in TUI and output
   -U, --unicode              █▓ Use Unicode block characters for graph
-  -u, --unit <bit|byte>      📐 Display unit: bit (default) or byte
+  -u, --unit <UNIT>          📐 Display unit: bit (default), byte, bit-si, byte-si, bit-iec, byte-iec
   -b, --bar-style <STYLE>    🎨 Bar style: fill (default), color, plain
       --in-color <HEX>       ⬇️  Incoming graph color, hex RGB (e.g. 0x00d7ff)
       --out-color <HEX>      ⬆️  Outgoing graph color, hex RGB (e.g. 0xffaf00)
   -m, --max <VALUE>          📏 Fixed graph Y-axis max (e.g. 100M, 1G). Default: auto
   -n, --no-graph             📋 Hide traffic graphs, show only statistics
+      --median <N>           🧹 Median-filter current speed over N samples (3-9)
+      --display-smooth [N]   🌊 Smooth the plotted graph only (±N samples, default 2)
+      --downsample [MODE]    🗜️ Fit all history into the graph: max, avg or last per column
+      --live-value           🔢 Print current value above the newest graph column
+      --heat                 🔥 Color graph by height (green → yellow → red)
+      --no-total             🙈 Hide machine-wide total (Σ) in the header
+      --no-label-rate        🏷️ Keep panel labels to the scale (no current speed)
+      --no-restore-history   🆕 Start with empty graphs (don't restore last run)
+      --pcap                 🧬 Split current device traffic by protocol (TCP/UDP)
+      --idle-alert <SECS>    🔕 Beep + flash header when a device is silent for SECS
+      --alert-rx <SPEED>     🚨 Alert when incoming speed exceeds SPEED (also --alert-tx)
+      --gauge                📊 Show a utilization gauge (percent of --max) instead of graph
+      --graph-chars <CHARS>  🔣 Graph glyphs: ascii, block, shade, eighths, or "full,partial,empty"
+      --bg-char <CHAR>       ⬜ Empty-cell background: space (default), dot, dash
+      --cumulative           📈 Mini graph of bytes transferred this session
+      --devices-json         🧾 Print interfaces as JSON and exit
+      --backend <BACKEND>    🧲 Counter source: auto, procfs or netlink (Linux)
+      --netns <NAME>         🐳 Enter a network namespace first (Linux, needs root)
+      --config <PATH>        📝 Read options from PATH instead of ~/.config/winload/config.toml
+      --no-config            📝 Ignore the config file
+      --remote <USER@HOST>   🛰️  Monitor a remote Linux host over ssh
+      --fade                 🌗 Fade older graph columns, keep recent ones bright
+      --split <IN%>          ↕️  Incoming panel height share (10-90, default 50)
+      --fps <N>              🎞️  Redraw rate, independent of sampling interval
+      --socket <PATH>        🔌 Broadcast NDJSON metrics on a Unix socket
+      --noise-floor <SPEED>  🔇 Hide background traffic below SPEED in graph
+      --noise-floor-stats    🔇 Also subtract noise floor from statistics
+      --crop-min <SPEED>     ✂️ Graph bottom: lower speeds draw empty
+      --crop-max <SPEED>     ✂️ Graph top: higher speeds clip to full height
+      --record <FILE>        ⏺️  Record raw counters for later replay
+      --replay <FILE>        ⏯️  Replay a recorded session
+      --status [FIELDS]      📟 Status bar: total,devices,elapsed,unit,frozen,timing,ratio
+      --shared-scale         ⚖️  Same graph scale for incoming and outgoing
+      --fixed-unit           📌 Same unit for all speeds in a panel (from its scale)
+      --compare <A,B>        🆚 Two devices side by side, one shared scale
+      --eta-target <BYTES>   ⏳ ETA until BYTES received this session
+      --source-cmd <CMD>     🧩 Read "<dev> <rx> <tx>" counters from a command
+      --device-source <NAME=BACKEND>  🔀 Per-device backend: os or pcap (e.g. eth1=pcap)
+      --filter <EXPR>        🎯 Count only matching packets (BPF subset, e.g. "host 10.0.0.5")
+      --log-alerts           📜 Also log alert edges to syslog / Windows Event Log
+      --top <N>              🏆 Table of the top N processes by TCP throughput (Linux/Windows)
+      --avg-method <M>       🧮 Avg computation: sma (steady) or ema (responsive)
+      --ema-alpha <A>        🎚️  EMA smoothing factor in (0, 1] (default 0.2)
+      --show-ceiling         📏 Dashed ceiling line with its value on the graph's top row
+      --time-grid <SECS>     🕓 Faint vertical line every SECS of sample time on the graph
+      --thousands-sep <SEP>  🔢 Exact-total digit grouping: char, none or auto (locale)
+      --window-total <SECS>  🪟 Extra stats line: bytes moved in the last SECS
+      --recent-max [SECS]    📈 Extra stats line: peak speed in the last SECS
+      --legend               🗺️  Graph symbol/color legend (toggle with l)
+      --duration <SECS>      ⏱️  Exit automatically after SECS (exit code 1 = alert, 2 = no data)
+      --summary              🧾 Print per-device stats to stdout on exit
+      --report-json [PATH]   🗃️  Write a JSON report of all devices on exit (stdout without PATH)
+      --verbose              🔬 Show raw counters / delta / elapsed behind the rate (v)
+      --spikes <N>           🏔️ Keep the N biggest bursts for the spike log (s key)
+      --countdown            ⏳ Show "next in 0.3s" countdown to the next sample
+      --confirm-quit         🛑 Ask "Quit? (y/n)" before q / Esc exits
+      --inline               🧾 Draw inline below the prompt; last frame stays in scrollback
+      --lines <N>            📏 Height of the --inline viewport (default 20)
 
 🪟 Windows Loopback:
       --npcap                🟢 Use Npcap to capture loopback traffic (recommended)
@@ -380,6 +2265,24 @@ nload-like TUI tool for Windows/Linux/macOS
 
 ⌨️  Keybindings:
   ⬅️/➡️ or ⬆️/⬇️              Switch network device
+  Tab / Shift+Tab           ↹  Cycle to next / previous device
+  * / g                     ⭐ Star current device / jump to next starred
+  u                         📐 Toggle unit (bit/s ↔ byte/s)
+  k                         📐 Cycle unit prefixes: nload (1024) → SI (kB) → IEC (KiB)
+  [ / ]                     ↕️  Shrink / grow incoming panel
+  + / - / 0                 🔭 Zoom graph in / out / reset (time window)
+  m / Enter                 🗂️  Overview grid of all devices / open the selected one
+  p / o                     👥 Per-process bandwidth page / change its sort column
+  x                         🔀 Swap the two --compare columns
+  t                         🧮 Toggle total: human-readable ↔ exact bytes
+  f                         🧊 Freeze / unfreeze graph (stats stay live)
+  a                         🌐 Cycle header address: v4+v6 / v4 / v6
+  i                         🔍 Debug info overlay (↑/↓ scroll)
+  l                         🗺️  Show / hide graph legend
+  c                         📋 Copy current stats to clipboard
+  e                         💾 Export current device history to a CSV file
+  v                         🔬 Show / hide rate inputs (counters, delta, elapsed)
+  s                         🏔️ Show / hide spike log (biggest bursts)
   q / Esc                   🚪 Quit
 
 💡 Examples:
@@ -398,22 +2301,302 @@ fn main() -> io::Result<()> {
     // 如果同时传了 --help + --emoji，输出带 emoji 的帮助后退出
     maybe_print_emoji_help();
 
-    let args = Args::parse();
+    let mut args = load_args();
+    if let Err(e) = args.validate() {
+        eprintln!("Error: {e}");
+        std::process::exit(2);
+    }
+
+    // --netns: 在任何线程与数据源创建之前切换网络命名空间，之后的所有采集都作用于该命名空间
+    if let Some(ref name) = args.netns {
+        if let Err(e) = netns::enter(name) {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
 
-    // 如果传入 --debug-info，打印接口信息后退出
+    // 如果传入 --bench，测量采集开销后退出
+    if let Some(secs) = args.bench {
+        run_bench(Duration::from_secs(secs), args.backend);
+        return Ok(());
+    }
+
+    // 如果传入 --devices-json，以 JSON 打印接口列表后退出
+    if args.devices_json {
+        print!("{}", Collector::new().devices_json());
+        return Ok(());
+    }
+
+    // --remote / --source-cmd / --replay: 进入 TUI 前先准备好数据源，失败时直接报错退出
+    let source: Box<dyn TrafficSource> = if let Some(ref host) = args.remote {
+        match remote::connect(host, args.interval) {
+            Ok(source) => Box::new(source),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(ref cmd) = args.source_cmd {
+        match CommandSource::new(cmd) {
+            Ok(source) => Box::new(source),
+            Err(e) => {
+                eprintln!("Error: --source-cmd: {e}");
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(ref path) = args.replay {
+        // --replay: 每个节拍播放录制文件中的一帧
+        match ReplaySource::open(path) {
+            Ok(source) => Box::new(source),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+    } else {
+        // --backend: 本机计数器的读取方式，不可用时直接报错退出
+        let local = match backend::open(args.backend) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        };
+        if args.device_source.is_empty() {
+            local
+        } else {
+            // --device-source: 系统计数器为主，指定网卡改用其他后端
+            Box::new(HybridSource::new(local, &args.device_source, args.filter.as_ref()))
+        }
+    };
+
+    // 如果传入 --debug-info，打印数据源提供的接口信息后退出
     if args.debug_info {
-        let collector = Collector::new();
         if args.emoji {
             println!("\n🔍🌐 Network Interfaces Debug Info 🖧✨");
         }
-        collector.print_debug_info();
+        print!("{}", debug_info_text(source.as_ref(), &source_label(&args)));
         if args.emoji {
             println!("🏁 Done! Happy debugging! 🎉🐛");
         }
         return Ok(());
     }
-    let mut terminal = ratatui::init();
-    let result = run(&mut terminal, args);
+
+    // 先取出标准库默认的 panic 钩子 (终端恢复后的 panic 用它打印)，ratatui::init 的钩子随后被包起来
+    let default_hook = std::panic::take_hook();
+    // --inline: 不进入备用屏幕，在提示符下方占用固定行数绘制
+    let inline = args.inline;
+    let mut terminal = if inline {
+        // 内联视口需要读取光标位置，终端不响应时报错退出而不是 panic
+        let options = ratatui::TerminalOptions {
+            viewport: ratatui::Viewport::Inline(args.lines),
+        };
+        match ratatui::try_init_with_options(options) {
+            Ok(terminal) => terminal,
+            Err(e) => {
+                ratatui::restore();
+                eprintln!("Error: --inline is not supported by this terminal: {e}");
+                std::process::exit(1);
+            }
+        }
+    } else {
+        ratatui::init()
+    };
+    install_panic_hook(default_hook);
+    let report_path = args.report_json.clone();
+    let result = run(&mut terminal, args, source);
+    // 后台线程 panic: panic 钩子已恢复终端并打印信息，不再重复恢复，按 panic 的退出码退出
+    if PANICKED.load(Ordering::SeqCst) {
+        std::process::exit(101);
+    }
+    if inline {
+        // 光标移到最后一帧的末行，恢复后换行，使最后一帧留在滚动历史中、提示符出现在其下方
+        let area = terminal.get_frame().area();
+        let _ = terminal.set_cursor_position((0, area.bottom().saturating_sub(1)));
+    }
     ratatui::restore();
-    result
+    if inline {
+        println!();
+    }
+    let outcome = result?;
+    // --summary: 恢复终端后把各网卡的统计打印到标准输出
+    if let Some(summary) = outcome.summary {
+        print!("{summary}");
+    }
+    // --report-json: 不带路径 (即 "-") 时输出到标准输出
+    if let (Some(report), Some(path)) = (outcome.report, report_path) {
+        if path.as_os_str() == "-" {
+            print!("{report}");
+        } else if let Err(e) = std::fs::write(&path, report) {
+            eprintln!("Error: --report-json {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    }
+    if outcome.exit_code != 0 {
+        std::process::exit(outcome.exit_code);
+    }
+    Ok(())
+}
+
+/// 是否已有线程 panic (终端已由 panic 钩子恢复)；主循环见到后退出
+static PANICKED: AtomicBool = AtomicBool::new(false);
+
+/// 数据源说明: --remote / --source-cmd / --replay，或本机计数器后端 (及 --netns、--device-source)
+fn source_label(args: &Args) -> String {
+    if let Some(ref host) = args.remote {
+        return format!("remote host {host} (/proc/net/dev over ssh)");
+    }
+    if let Some(ref cmd) = args.source_cmd {
+        return format!("command `{cmd}`");
+    }
+    if let Some(ref path) = args.replay {
+        return format!("replay of {}", path.display());
+    }
+    let mut label = format!("local counters, backend {}", args.backend.describe());
+    if let Some(ref name) = args.netns {
+        label.push_str(&format!(", network namespace {name}"));
+    }
+    for (device, backend) in &args.device_source {
+        label.push_str(&format!(", {device}={}", backend.name()));
+    }
+    label
+}
+
+/// `--debug-info` 与 `i` 浮层的内容: 当前数据源提供的设备信息与状态
+fn debug_info_text(source: &dyn TrafficSource, label: &str) -> String {
+    use std::fmt::Write;
+
+    let devices = source.devices();
+    let mut out = String::new();
+    let _ = writeln!(out, "\n=== Network Interfaces Debug Info ===");
+    let _ = writeln!(out, "Source: {label}");
+    let _ = writeln!(out, "Status: {}", source.status().unwrap_or_else(|| "ok".to_string()));
+    let _ = writeln!(out, "Devices: {}\n", devices.len());
+
+    let or_unknown = |v: Option<String>| v.unwrap_or_else(|| "(unknown)".to_string());
+    let list = |v: &[String]| if v.is_empty() { "(none)".to_string() } else { v.join(", ") };
+    for dev in &devices {
+        let _ = writeln!(out, "Interface: {}", dev.name);
+        let _ = writeln!(out, "  Index: {}", or_unknown(dev.index.map(|i| i.to_string())));
+        let _ = writeln!(out, "  Type: {}", or_unknown(dev.kind.clone()));
+        let _ = writeln!(out, "  MAC address: {}", or_unknown(dev.mac.clone()));
+        let link = source.link_up(&dev.name).or(dev.link_up);
+        let _ = writeln!(out, "  Link: {}", or_unknown(link.map(|up| if up { "up" } else { "down" }.to_string())));
+        let _ = writeln!(out, "  Speed: {}", or_unknown(dev.link_speed_mbps.map(|s| format!("{s} Mbit/s"))));
+        if dev.vpn == Some(true) {
+            let _ = writeln!(out, "  VPN: yes");
+        }
+        let _ = writeln!(out, "  IPv4: {}", list(&dev.addrs));
+        let _ = writeln!(out, "  IPv6: {}", list(&dev.addrs6));
+        let _ = writeln!(out);
+    }
+    out
+}
+
+/// --bench: 不绘制界面，连续调用 `--backend` 选定数据源的 `collect` 直到 `duration` 用完，输出一行汇总
+fn run_bench(duration: Duration, backend: CounterBackend) {
+    let mut collector = match backend::open(backend) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+    let interfaces = collector.devices().len();
+    let mut calls: u32 = 0;
+    let mut max_latency = Duration::ZERO;
+    let started = Instant::now();
+    while started.elapsed() < duration {
+        let t = Instant::now();
+        std::hint::black_box(collector.collect());
+        max_latency = max_latency.max(t.elapsed());
+        calls += 1;
+    }
+    let elapsed = started.elapsed();
+    let avg = elapsed / calls.max(1);
+    println!(
+        "bench [{}]: {calls} collects in {:.2}s ({:.0}/s), avg {:.1}us, max {:.1}us, {interfaces} interfaces",
+        backend.name(),
+        elapsed.as_secs_f64(),
+        calls as f64 / elapsed.as_secs_f64(),
+        avg.as_secs_f64() * 1e6,
+        max_latency.as_secs_f64() * 1e6,
+    );
+}
+
+/// 安装 panic 钩子 (包在 ratatui::init 的钩子外面)
+///
+/// 任何线程 (主线程，或 --remote / --pcap / 回环捕获等后台线程) 第一次 panic 时交给
+/// ratatui 的钩子: 恢复终端 (退出备用屏幕 / raw 模式) 后打印 panic 信息，并让主循环退出，
+/// 避免在已恢复的终端上继续绘制；之后的 panic 不再重复恢复终端，只按默认方式打印。
+fn install_panic_hook(default_hook: Box<dyn Fn(&std::panic::PanicHookInfo<'_>) + Sync + Send>) {
+    let ratatui_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if PANICKED.swap(true, Ordering::SeqCst) {
+            default_hook(info);
+        } else {
+            ratatui_hook(info);
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_zero_is_rejected_and_tiny_values_are_clamped() {
+        assert!(clamp_interval("--interval", 0).is_err());
+        assert_eq!(clamp_interval("--interval", 1), Ok(MIN_INTERVAL_MS));
+        assert_eq!(clamp_interval("--interval", 9), Ok(MIN_INTERVAL_MS));
+        assert_eq!(clamp_interval("--interval", 10), Ok(10));
+        assert_eq!(clamp_interval("--interval", 500), Ok(500));
+    }
+
+    #[test]
+    fn rebound_key_replaces_the_default_and_conflicts_are_rejected() {
+        let lookup = |table: &[(KeyCode, KeyAction)], c: char| {
+            table.iter().find(|(k, _)| *k == KeyCode::Char(c)).map(|&(_, a)| a)
+        };
+        let table = key_bindings(&[]).unwrap();
+        assert_eq!(lookup(&table, 'q'), Some(KeyAction::Quit));
+        assert_eq!(lookup(&table, 'x'), Some(KeyAction::Swap));
+
+        // quit 与 swap 互换按键
+        let table = key_bindings(&[(KeyAction::Quit, 'x'), (KeyAction::Swap, 'q')]).unwrap();
+        assert_eq!(lookup(&table, 'x'), Some(KeyAction::Quit));
+        assert_eq!(lookup(&table, 'X'), Some(KeyAction::Quit));
+        assert_eq!(lookup(&table, 'q'), Some(KeyAction::Swap));
+        assert!(table.contains(&(KeyCode::Esc, KeyAction::Quit)));
+
+        // x 仍属于 swap
+        let err = key_bindings(&[(KeyAction::Quit, 'x')]).unwrap_err();
+        assert!(err.contains("'quit'") && err.contains("'swap'"), "{err}");
+    }
+
+    #[test]
+    fn same_name_devices_merge_only_without_distinct_indexes() {
+        let dev = |name: &str, index: Option<u32>, addr: &str| DeviceInfo {
+            name: name.to_string(),
+            index,
+            addrs: vec![addr.to_string()],
+            ..Default::default()
+        };
+        // 索引不同: 各自保留，快照键也不同
+        let merged = collector::merge_duplicate_devices(vec![
+            dev("Wi-Fi", Some(7), "10.0.0.2"),
+            dev("Wi-Fi", Some(9), "10.0.0.3"),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_ne!(merged[0].key(), merged[1].key());
+
+        // 都没有索引: 按名称合并，地址取并集
+        let merged = collector::merge_duplicate_devices(vec![
+            dev("eth0", None, "10.0.0.2"),
+            dev("eth0", None, "10.0.0.3"),
+        ]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].addrs, ["10.0.0.2", "10.0.0.3"]);
+        assert_eq!(merged[0].key(), DeviceKey::Name("eth0".to_string()));
+    }
 }
@@ -0,0 +1,100 @@
+//! 进入其他网络命名空间 (--netns，仅 Linux)
+//! 容器的 veth 等接口位于各自的网络命名空间中，宿主机默认看不到它们的计数器。
+//!
+//! [`enter`] 在采集开始前把整个进程切换到目标命名空间，之后所有数据源
+//! (sysinfo、/proc/net/dev、rtnetlink、抓包) 看到的都是该命名空间的接口:
+//!
+//! - `NAME` 对应 `ip netns add NAME` 创建的 `/run/netns/NAME`
+//! - 含 `/` 时视为命名空间文件路径，如 `/proc/<pid>/ns/net` (容器进程)
+//!
+//! `/sys/class/net` 按挂载时的命名空间显示接口，因此与 `ip netns exec` 一样，
+//! 还需要进入私有的挂载命名空间并重新挂载 sysfs。需要 root (CAP_SYS_ADMIN)。
+//! 必须在启动任何线程之前调用 (多线程进程不能 unshare 挂载命名空间)。
+
+/// 把当前进程切换到 `name` 指定的网络命名空间；失败时返回原因
+pub fn enter(name: &str) -> Result<(), String> {
+    platform::enter(name)
+}
+
+// ═══════════════════════════════════════════════════════════
+//  Linux: setns + 重新挂载 sysfs
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+    use std::path::PathBuf;
+    use std::ptr;
+
+    /// `ip netns` 存放命名空间文件的目录
+    const NETNS_RUN_DIR: &str = "/run/netns";
+
+    /// 解析 `--netns` 参数对应的命名空间文件
+    fn ns_path(name: &str) -> PathBuf {
+        if name.contains('/') {
+            PathBuf::from(name)
+        } else {
+            PathBuf::from(NETNS_RUN_DIR).join(name)
+        }
+    }
+
+    pub fn enter(name: &str) -> Result<(), String> {
+        let path = ns_path(name);
+        let file = File::open(&path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => format!(
+                "--netns: no network namespace '{name}' ({} not found; see `ip netns list`)",
+                path.display()
+            ),
+            io::ErrorKind::PermissionDenied => {
+                format!("--netns: cannot open {}: permission denied (run as root)", path.display())
+            }
+            _ => format!("--netns: cannot open {}: {e}", path.display()),
+        })?;
+
+        if unsafe { libc::setns(file.as_raw_fd(), libc::CLONE_NEWNET) } != 0 {
+            let e = io::Error::last_os_error();
+            return Err(match e.raw_os_error() {
+                Some(libc::EPERM) => format!(
+                    "--netns: not permitted to enter '{name}' (needs root / CAP_SYS_ADMIN)"
+                ),
+                Some(libc::EINVAL) => format!("--netns: {} is not a network namespace", path.display()),
+                _ => format!("--netns: setns failed: {e}"),
+            });
+        }
+        remount_sysfs()
+    }
+
+    /// 私有挂载命名空间中重新挂载 /sys，使 /sys/class/net 显示新命名空间的接口
+    fn remount_sysfs() -> Result<(), String> {
+        let fail = |what: &str| format!("--netns: {what} failed: {}", io::Error::last_os_error());
+        unsafe {
+            if libc::unshare(libc::CLONE_NEWNS) != 0 {
+                return Err(fail("unshare(CLONE_NEWNS)"));
+            }
+            // 挂载变化不传播回宿主机
+            if libc::mount(ptr::null(), c"/".as_ptr(), ptr::null(), libc::MS_SLAVE | libc::MS_REC, ptr::null()) != 0 {
+                return Err(fail("making / a slave mount"));
+            }
+            if libc::umount2(c"/sys".as_ptr(), libc::MNT_DETACH) != 0 {
+                return Err(fail("unmounting /sys"));
+            }
+            if libc::mount(c"sysfs".as_ptr(), c"/sys".as_ptr(), c"sysfs".as_ptr(), 0, ptr::null()) != 0 {
+                return Err(fail("mounting sysfs"));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+//  其他平台: 不支持
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    pub fn enter(_name: &str) -> Result<(), String> {
+        Err("--netns is only available on Linux".to_string())
+    }
+}
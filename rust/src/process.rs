@@ -0,0 +1,489 @@
+//! 按进程统计带宽 (--top)
+//! 读取每个 TCP 套接字的累计收发字节数，再按套接字所属进程汇总，得到各进程的当前速率。
+//!
+//! - Linux: 通过 NETLINK_SOCK_DIAG 读取 `tcp_info` 中的 `bytes_received` / `bytes_acked`
+//!   (与 `ss -ti` 相同，内核 4.2+)，再扫描 `/proc/<pid>/fd` 把套接字 inode 对应到进程
+//! - Windows: `GetExtendedTcpTable` 列出 IPv4 连接及所属进程，`GetPerTcpConnectionEStats`
+//!   读取每条连接的累计收发字节数 (需要管理员权限开启统计)
+//! - 其他平台暂不支持，[`ProcessMonitor::new`] 返回错误说明
+//!
+//! 只统计 TCP；非 root 运行时只能看到自己用户的进程，其余套接字不计入。
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// 某个进程在最近一个采样间隔内的速率
+#[derive(Debug, Clone)]
+pub struct ProcessRate {
+    pub pid: u32,
+    /// 进程名 (`/proc/<pid>/comm`)
+    pub name: String,
+    /// 接收速率 (bytes/s)
+    pub recv: f64,
+    /// 发送速率 (bytes/s)
+    pub sent: f64,
+}
+
+impl ProcessRate {
+    /// 收发速率之和，用于排序
+    pub fn total(&self) -> f64 {
+        self.recv + self.sent
+    }
+}
+
+/// 进程列表的排序方式 (o 键循环切换)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProcessSort {
+    /// 收发合计从高到低
+    #[default]
+    Total,
+    /// 接收速率从高到低
+    Recv,
+    /// 发送速率从高到低
+    Sent,
+    /// 进程名 (字母序)
+    Name,
+    /// PID 从小到大
+    Pid,
+}
+
+impl ProcessSort {
+    /// 循环切换到下一种排序
+    pub fn next(self) -> Self {
+        match self {
+            ProcessSort::Total => ProcessSort::Recv,
+            ProcessSort::Recv => ProcessSort::Sent,
+            ProcessSort::Sent => ProcessSort::Name,
+            ProcessSort::Name => ProcessSort::Pid,
+            ProcessSort::Pid => ProcessSort::Total,
+        }
+    }
+
+    /// 界面中显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            ProcessSort::Total => "total",
+            ProcessSort::Recv => "in",
+            ProcessSort::Sent => "out",
+            ProcessSort::Name => "name",
+            ProcessSort::Pid => "pid",
+        }
+    }
+
+    /// 按该方式排序，相同时按 PID 保证顺序稳定
+    pub fn sort(self, rates: &mut [ProcessRate]) {
+        rates.sort_by(|a, b| {
+            let order = match self {
+                ProcessSort::Total => b.total().total_cmp(&a.total()),
+                ProcessSort::Recv => b.recv.total_cmp(&a.recv),
+                ProcessSort::Sent => b.sent.total_cmp(&a.sent),
+                ProcessSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                ProcessSort::Pid => std::cmp::Ordering::Equal,
+            };
+            order.then(a.pid.cmp(&b.pid))
+        });
+    }
+}
+
+/// 按进程统计带宽的采样器
+pub struct ProcessMonitor {
+    /// 上一次采样时各套接字 (inode) 的累计 (收, 发) 字节数
+    prev: HashMap<u64, (u64, u64)>,
+    last: Option<Instant>,
+}
+
+impl ProcessMonitor {
+    /// 检查当前平台是否支持，并做一次初始采样
+    pub fn new() -> Result<Self, String> {
+        let prev = platform::tcp_sockets()?;
+        Ok(Self {
+            prev,
+            last: Some(Instant::now()),
+        })
+    }
+
+    /// 采样一次，返回有流量的进程，按总速率从高到低排序
+    ///
+    /// 只计算两次采样中都存在的套接字的增量，新建 / 关闭的连接不会产生尖峰。
+    pub fn sample(&mut self) -> Vec<ProcessRate> {
+        let Ok(sockets) = platform::tcp_sockets() else {
+            return Vec::new();
+        };
+        let now = Instant::now();
+        let elapsed = self
+            .last
+            .map_or(0.0, |t| now.duration_since(t).as_secs_f64());
+        self.last = Some(now);
+        let prev = std::mem::replace(&mut self.prev, sockets);
+        if elapsed <= 0.0 {
+            return Vec::new();
+        }
+
+        let owners = platform::socket_owners();
+        let mut per_pid: HashMap<u32, (u64, u64)> = HashMap::new();
+        for (inode, &(recv, sent)) in &self.prev {
+            let (Some(&(prev_recv, prev_sent)), Some(&pid)) = (prev.get(inode), owners.get(inode))
+            else {
+                continue;
+            };
+            let entry = per_pid.entry(pid).or_default();
+            entry.0 += recv.saturating_sub(prev_recv);
+            entry.1 += sent.saturating_sub(prev_sent);
+        }
+
+        let mut rates: Vec<ProcessRate> = per_pid
+            .into_iter()
+            .filter(|(_, (recv, sent))| recv + sent > 0)
+            .map(|(pid, (recv, sent))| ProcessRate {
+                pid,
+                name: platform::process_name(pid),
+                recv: recv as f64 / elapsed,
+                sent: sent as f64 / elapsed,
+            })
+            .collect();
+        ProcessSort::Total.sort(&mut rates);
+        rates
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+//  Linux: NETLINK_SOCK_DIAG + /proc
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::{fs, io, mem};
+
+    const SOCK_DIAG_BY_FAMILY: u16 = 20;
+    const INET_DIAG_INFO: u16 = 2;
+    /// `struct nlmsghdr` 长度
+    const NLMSG_HDR_LEN: usize = 16;
+    /// `struct inet_diag_msg` 长度
+    const INET_DIAG_MSG_LEN: usize = 72;
+    /// `struct inet_diag_msg` 中 `idiag_inode` 的偏移
+    const INODE_OFFSET: usize = 68;
+    /// `struct tcp_info` 中 `tcpi_bytes_acked` / `tcpi_bytes_received` 的偏移
+    const BYTES_ACKED_OFFSET: usize = 120;
+    const BYTES_RECEIVED_OFFSET: usize = 128;
+
+    fn u32_at(data: &[u8], off: usize) -> Option<u32> {
+        Some(u32::from_ne_bytes(data.get(off..off + 4)?.try_into().ok()?))
+    }
+
+    fn u64_at(data: &[u8], off: usize) -> Option<u64> {
+        Some(u64::from_ne_bytes(data.get(off..off + 8)?.try_into().ok()?))
+    }
+
+    /// 读取所有 TCP 套接字的 inode → 累计 (收, 发) 字节数
+    pub fn tcp_sockets() -> Result<HashMap<u64, (u64, u64)>, String> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                libc::NETLINK_SOCK_DIAG,
+            )
+        };
+        if fd < 0 {
+            return Err(format!(
+                "Failed to open sock_diag netlink socket: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        let mut sockets = HashMap::new();
+        let result = [libc::AF_INET, libc::AF_INET6]
+            .into_iter()
+            .try_for_each(|family| dump_family(fd, family as u8, &mut sockets));
+        unsafe { libc::close(fd) };
+        result.map(|()| sockets)
+    }
+
+    /// 发送一次 SOCK_DIAG_BY_FAMILY dump 请求并解析所有应答
+    fn dump_family(fd: i32, family: u8, out: &mut HashMap<u64, (u64, u64)>) -> Result<(), String> {
+        // nlmsghdr (16) + inet_diag_req_v2 (56)
+        let mut req = [0u8; NLMSG_HDR_LEN + 56];
+        let len = req.len() as u32;
+        req[0..4].copy_from_slice(&len.to_ne_bytes());
+        req[4..6].copy_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+        let flags = (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16;
+        req[6..8].copy_from_slice(&flags.to_ne_bytes());
+        req[16] = family;
+        req[17] = libc::IPPROTO_TCP as u8;
+        req[18] = 1 << (INET_DIAG_INFO - 1);
+        // idiag_states: 所有状态
+        req[20..24].copy_from_slice(&u32::MAX.to_ne_bytes());
+
+        let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as u16;
+        let sent = unsafe {
+            libc::sendto(
+                fd,
+                req.as_ptr() as *const libc::c_void,
+                req.len(),
+                0,
+                &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if sent < 0 {
+            return Err(format!("sock_diag request failed: {}", io::Error::last_os_error()));
+        }
+
+        let mut buf = vec![0u8; 32 * 1024];
+        loop {
+            let n = unsafe { libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+            if n < 0 {
+                return Err(format!("sock_diag read failed: {}", io::Error::last_os_error()));
+            }
+            let mut data = &buf[..n as usize];
+            while data.len() >= NLMSG_HDR_LEN {
+                let msg_len = u32_at(data, 0).unwrap_or(0) as usize;
+                if msg_len < NLMSG_HDR_LEN || msg_len > data.len() {
+                    break;
+                }
+                let msg_type = u16::from_ne_bytes([data[4], data[5]]);
+                if msg_type == libc::NLMSG_DONE as u16 {
+                    return Ok(());
+                }
+                if msg_type == libc::NLMSG_ERROR as u16 {
+                    return Err("sock_diag returned an error (kernel without inet_diag?)".to_string());
+                }
+                if let Some((inode, counters)) = parse_diag_msg(&data[NLMSG_HDR_LEN..msg_len]) {
+                    out.insert(inode, counters);
+                }
+                // 消息按 4 字节对齐
+                let aligned = (msg_len + 3) & !3;
+                data = data.get(aligned..).unwrap_or_default();
+            }
+        }
+    }
+
+    /// 解析一条 inet_diag_msg，返回 (inode, (收, 发))；TIME_WAIT 等无 inode 的套接字跳过
+    fn parse_diag_msg(msg: &[u8]) -> Option<(u64, (u64, u64))> {
+        let inode = u32_at(msg, INODE_OFFSET)?;
+        if inode == 0 {
+            return None;
+        }
+        let mut attrs = msg.get(INET_DIAG_MSG_LEN..)?;
+        while attrs.len() >= 4 {
+            let rta_len = u16::from_ne_bytes([attrs[0], attrs[1]]) as usize;
+            let rta_type = u16::from_ne_bytes([attrs[2], attrs[3]]);
+            if rta_len < 4 || rta_len > attrs.len() {
+                break;
+            }
+            if rta_type == INET_DIAG_INFO {
+                let info = &attrs[4..rta_len];
+                let sent = u64_at(info, BYTES_ACKED_OFFSET)?;
+                let recv = u64_at(info, BYTES_RECEIVED_OFFSET)?;
+                return Some((inode as u64, (recv, sent)));
+            }
+            attrs = attrs.get((rta_len + 3) & !3..).unwrap_or_default();
+        }
+        None
+    }
+
+    /// 扫描 /proc/<pid>/fd，建立套接字 inode → pid 的映射 (无权限的进程跳过)
+    pub fn socket_owners() -> HashMap<u64, u32> {
+        let mut owners = HashMap::new();
+        let Ok(procs) = fs::read_dir("/proc") else {
+            return owners;
+        };
+        for entry in procs.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+                continue;
+            };
+            let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+                continue;
+            };
+            for fd in fds.flatten() {
+                let Ok(target) = fs::read_link(fd.path()) else {
+                    continue;
+                };
+                // 链接目标形如 "socket:[12345]"
+                let inode = target
+                    .to_str()
+                    .and_then(|t| t.strip_prefix("socket:["))
+                    .and_then(|t| t.strip_suffix(']'))
+                    .and_then(|t| t.parse::<u64>().ok());
+                if let Some(inode) = inode {
+                    owners.entry(inode).or_insert(pid);
+                }
+            }
+        }
+        owners
+    }
+
+    pub fn process_name(pid: u32) -> String {
+        fs::read_to_string(format!("/proc/{pid}/comm"))
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_else(|_| "?".to_string())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+//  Windows: IP Helper 连接表 + 扩展 TCP 统计 (ESTATS)
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(all(windows, feature = "process"))]
+mod platform {
+    use super::*;
+    use std::hash::{Hash, Hasher};
+    use std::{mem, ptr};
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, ERROR_ACCESS_DENIED, ERROR_INSUFFICIENT_BUFFER, NO_ERROR,
+    };
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GetExtendedTcpTable, GetPerTcpConnectionEStats, SetPerTcpConnectionEStats,
+        TcpConnectionEstatsData, MIB_TCPROW_LH, MIB_TCPROW_LH_0, MIB_TCPROW_OWNER_PID,
+        MIB_TCPTABLE_OWNER_PID, TCP_ESTATS_DATA_ROD_v0, TCP_ESTATS_DATA_RW_v0,
+        TCP_TABLE_OWNER_PID_CONNECTIONS,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    const AF_INET: u32 = 2;
+
+    /// 读取 IPv4 TCP 连接表 (含所属进程 PID)
+    fn connections() -> Result<Vec<MIB_TCPROW_OWNER_PID>, String> {
+        let mut size = 0u32;
+        // 以 u32 为单位分配，保证表结构的对齐
+        let mut buf: Vec<u32> = Vec::new();
+        loop {
+            let ret = unsafe {
+                GetExtendedTcpTable(
+                    buf.as_mut_ptr().cast(),
+                    &mut size,
+                    0,
+                    AF_INET,
+                    TCP_TABLE_OWNER_PID_CONNECTIONS,
+                    0,
+                )
+            };
+            match ret {
+                NO_ERROR => break,
+                ERROR_INSUFFICIENT_BUFFER => buf = vec![0u32; (size as usize).div_ceil(4)],
+                e => return Err(format!("GetExtendedTcpTable failed (error {e})")),
+            }
+        }
+        if buf.is_empty() {
+            return Ok(Vec::new());
+        }
+        let table = buf.as_ptr() as *const MIB_TCPTABLE_OWNER_PID;
+        let rows = unsafe {
+            std::slice::from_raw_parts((*table).table.as_ptr(), (*table).dwNumEntries as usize)
+        };
+        Ok(rows.to_vec())
+    }
+
+    /// 以连接四元组代替 Linux 的 inode 作为套接字标识
+    fn connection_key(row: &MIB_TCPROW_OWNER_PID) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (row.dwLocalAddr, row.dwLocalPort, row.dwRemoteAddr, row.dwRemotePort).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// 读取所有 TCP 连接的累计 (收, 发) 字节数
+    ///
+    /// 扩展统计默认关闭，每次对连接开启一次 (已开启时无副作用)；开启后才开始计数，
+    /// 因此新连接在第二次采样起才有速率。
+    pub fn tcp_sockets() -> Result<HashMap<u64, (u64, u64)>, String> {
+        let rows = connections()?;
+        let mut sockets = HashMap::new();
+        let mut denied = 0;
+        for row in &rows {
+            let lh = MIB_TCPROW_LH {
+                Anonymous: MIB_TCPROW_LH_0 { dwState: row.dwState },
+                dwLocalAddr: row.dwLocalAddr,
+                dwLocalPort: row.dwLocalPort,
+                dwRemoteAddr: row.dwRemoteAddr,
+                dwRemotePort: row.dwRemotePort,
+            };
+            let rw = TCP_ESTATS_DATA_RW_v0 { EnableCollection: 1 };
+            let ret = unsafe {
+                SetPerTcpConnectionEStats(
+                    &lh,
+                    TcpConnectionEstatsData,
+                    &rw as *const _ as *const u8,
+                    0,
+                    mem::size_of::<TCP_ESTATS_DATA_RW_v0>() as u32,
+                    0,
+                )
+            };
+            if ret == ERROR_ACCESS_DENIED {
+                denied += 1;
+                continue;
+            }
+            let mut rod: TCP_ESTATS_DATA_ROD_v0 = unsafe { mem::zeroed() };
+            let ret = unsafe {
+                GetPerTcpConnectionEStats(
+                    &lh,
+                    TcpConnectionEstatsData,
+                    ptr::null_mut(),
+                    0,
+                    0,
+                    ptr::null_mut(),
+                    0,
+                    0,
+                    &mut rod as *mut _ as *mut u8,
+                    0,
+                    mem::size_of::<TCP_ESTATS_DATA_ROD_v0>() as u32,
+                )
+            };
+            if ret == NO_ERROR {
+                sockets.insert(connection_key(row), (rod.DataBytesIn, rod.DataBytesOut));
+            }
+        }
+        if !rows.is_empty() && denied == rows.len() {
+            return Err("Per-process statistics on Windows need administrator rights (run winload as administrator).".to_string());
+        }
+        Ok(sockets)
+    }
+
+    /// 连接标识 → 所属进程 PID
+    pub fn socket_owners() -> HashMap<u64, u32> {
+        connections()
+            .unwrap_or_default()
+            .iter()
+            .map(|row| (connection_key(row), row.dwOwningPid))
+            .collect()
+    }
+
+    /// 进程映像文件名 (不含路径)，无权查询的进程为 "?"
+    pub fn process_name(pid: u32) -> String {
+        let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+        if handle.is_null() {
+            return "?".to_string();
+        }
+        let mut buf = [0u16; 260];
+        let mut len = buf.len() as u32;
+        let ok = unsafe { QueryFullProcessImageNameW(handle, 0, buf.as_mut_ptr(), &mut len) };
+        unsafe { CloseHandle(handle) };
+        if ok == 0 {
+            return "?".to_string();
+        }
+        let path = String::from_utf16_lossy(&buf[..len as usize]);
+        path.rsplit('\\').next().unwrap_or_default().to_string()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+//  其他平台
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(not(any(target_os = "linux", all(windows, feature = "process"))))]
+mod platform {
+    use super::*;
+
+    pub fn tcp_sockets() -> Result<HashMap<u64, (u64, u64)>, String> {
+        Err("Per-process statistics are not available on this platform (Linux and Windows only).".to_string())
+    }
+
+    pub fn socket_owners() -> HashMap<u64, u32> {
+        HashMap::new()
+    }
+
+    pub fn process_name(_pid: u32) -> String {
+        String::new()
+    }
+}
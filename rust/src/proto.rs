@@ -0,0 +1,368 @@
+//! 按协议 (TCP / UDP / 其他) 拆分流量
+//! 在选中的网卡上抓包，按 IP 协议号累加收发字节数，作为额外的 [`TrafficSource`]。
+//!
+//! 提供两种后端:
+//! - Windows: 通过 Npcap (pcap crate，需 `npcap` feature，即默认构建)
+//! - Linux:   通过 AF_PACKET 原始套接字，无需 libpcap
+//!
+//! 抓包需要 root / 管理员权限 (Linux 上也可授予 CAP_NET_RAW)。
+//! 其他平台暂不支持，[`start`] 返回错误说明。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use winload::collector::{DeviceInfo, DeviceKey, Snapshot, TrafficSource};
+use crate::filter::PacketFilter;
+
+/// 拆分出的协议 (Oth = 其他)，顺序即显示顺序
+pub const PROTOCOLS: [&str; 3] = ["TCP", "UDP", "Oth"];
+
+/// 各协议的收发计数器 (线程安全，抓包线程累加，主线程读取)
+#[derive(Clone, Default)]
+pub struct ProtoCounters {
+    recv: Arc<[AtomicU64; 3]>,
+    sent: Arc<[AtomicU64; 3]>,
+}
+
+impl ProtoCounters {
+    /// 累加一个包: `proto` 为 [`PROTOCOLS`] 下标
+    pub fn add(&self, proto: usize, incoming: bool, bytes: u64) {
+        let slot = if incoming { &self.recv } else { &self.sent };
+        slot[proto].fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// 读取某协议的 (收, 发) 累计字节数
+    pub fn get(&self, proto: usize) -> (u64, u64) {
+        (
+            self.recv[proto].load(Ordering::Relaxed),
+            self.sent[proto].load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// 协议拆分数据源
+///
+/// 每个协议表现为一个伪设备 (名称见 [`PROTOCOLS`])，
+/// 因此可以直接复用 [`winload::StatisticsEngine`] 计算各协议的速率。
+pub struct ProtoSource {
+    /// 被抓包的网卡名 (与 [`DeviceInfo::name`] 一致)
+    pub device: String,
+    counters: ProtoCounters,
+    start: Instant,
+}
+
+impl TrafficSource for ProtoSource {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        PROTOCOLS
+            .iter()
+            .map(|p| DeviceInfo {
+                name: p.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn collect(&mut self) -> HashMap<DeviceKey, Snapshot> {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        PROTOCOLS
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let (recv, sent) = self.counters.get(i);
+                (
+                    DeviceKey::Name(p.to_string()),
+                    Snapshot {
+                        elapsed_secs: elapsed,
+                        bytes_recv: recv,
+                        bytes_sent: sent,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// 在 `info` 对应的网卡上启动后台抓包
+///
+/// 指定 `filter` 时只统计匹配的包 (--filter)。
+/// 失败时 (无权限、网卡不存在、平台不支持) 返回可直接展示给用户的错误信息。
+pub fn start(info: &DeviceInfo, filter: Option<PacketFilter>) -> Result<ProtoSource, String> {
+    let counters = ProtoCounters::default();
+    platform::start(info, counters.clone(), filter)?;
+    Ok(ProtoSource {
+        device: info.name.clone(),
+        counters,
+        start: Instant::now(),
+    })
+}
+
+/// 按 IP 协议号归类: 6 = TCP, 17 = UDP
+#[cfg_attr(not(any(target_os = "linux", all(target_os = "windows", feature = "npcap"))), allow(dead_code))]
+fn proto_index(ip_proto: u8) -> usize {
+    match ip_proto {
+        6 => 0,
+        17 => 1,
+        _ => 2,
+    }
+}
+
+/// 解析一个 IP 包头，返回 (协议下标, 源地址, 目的地址)
+#[cfg_attr(not(any(target_os = "linux", all(target_os = "windows", feature = "npcap"))), allow(dead_code))]
+fn parse_ip(data: &[u8]) -> Option<(usize, std::net::IpAddr, std::net::IpAddr)> {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    match data.first()? >> 4 {
+        4 if data.len() >= 20 => {
+            let src: [u8; 4] = data[12..16].try_into().ok()?;
+            let dst: [u8; 4] = data[16..20].try_into().ok()?;
+            Some((
+                proto_index(data[9]),
+                IpAddr::V4(Ipv4Addr::from(src)),
+                IpAddr::V4(Ipv4Addr::from(dst)),
+            ))
+        }
+        6 if data.len() >= 40 => {
+            let src: [u8; 16] = data[8..24].try_into().ok()?;
+            let dst: [u8; 16] = data[24..40].try_into().ok()?;
+            Some((
+                proto_index(data[6]),
+                IpAddr::V6(Ipv6Addr::from(src)),
+                IpAddr::V6(Ipv6Addr::from(dst)),
+            ))
+        }
+        _ => None,
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+//  Linux 实现 (AF_PACKET)
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::ffi::CString;
+    use std::{io, mem, thread};
+
+    pub fn start(
+        info: &DeviceInfo,
+        counters: ProtoCounters,
+        filter: Option<PacketFilter>,
+    ) -> Result<(), String> {
+        let c_name = CString::new(info.name.as_str())
+            .map_err(|_| format!("Invalid interface name '{}'", info.name))?;
+        let ifindex = unsafe { libc::if_nametoindex(c_name.as_ptr()) };
+        if ifindex == 0 {
+            return Err(format!("Unknown interface '{}'", info.name));
+        }
+
+        // SOCK_DGRAM: 内核去掉链路层头，数据从 IP 头开始，兼容以太网 / tun / lo
+        let protocol = (libc::ETH_P_ALL as u16).to_be();
+        let fd = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_DGRAM, protocol as i32) };
+        if fd < 0 {
+            return Err(format!(
+                "Failed to open packet socket: {}\n\
+                 Run as root or grant CAP_NET_RAW (sudo setcap cap_net_raw+ep $(which winload)).",
+                io::Error::last_os_error()
+            ));
+        }
+
+        let mut addr: libc::sockaddr_ll = unsafe { mem::zeroed() };
+        addr.sll_family = libc::AF_PACKET as u16;
+        addr.sll_protocol = protocol;
+        addr.sll_ifindex = ifindex as i32;
+        let ret = unsafe {
+            libc::bind(
+                fd,
+                &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            let err = io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(format!("Failed to bind packet socket to '{}': {err}", info.name));
+        }
+
+        thread::Builder::new()
+            .name("winload-proto".into())
+            .spawn(move || {
+                // 只需要 IP 头与端口，MSG_TRUNC 让 recvfrom 返回包的真实长度。
+                // 带选项的 IPv4 头最长 60 字节，IPv6 还可能有扩展头，再加上 L4 端口，
+                // 留足 256 字节，否则 --filter port 匹配不到这些包
+                let mut buf = [0u8; 256];
+                loop {
+                    let mut from: libc::sockaddr_ll = unsafe { mem::zeroed() };
+                    let mut from_len = mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t;
+                    let n = unsafe {
+                        libc::recvfrom(
+                            fd,
+                            buf.as_mut_ptr() as *mut libc::c_void,
+                            buf.len(),
+                            libc::MSG_TRUNC,
+                            &mut from as *mut libc::sockaddr_ll as *mut libc::sockaddr,
+                            &mut from_len,
+                        )
+                    };
+                    if n < 0 {
+                        if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                            continue;
+                        }
+                        break;
+                    }
+                    let len = n as usize;
+                    let data = &buf[..len.min(buf.len())];
+                    let Some((proto, _, _)) = parse_ip(data) else {
+                        continue;
+                    };
+                    if filter.as_ref().is_some_and(|f| !f.matches(data)) {
+                        continue;
+                    }
+                    // 内核直接给出方向，不需要比对本机地址
+                    let incoming = from.sll_pkttype != libc::PACKET_OUTGOING;
+                    counters.add(proto, incoming, len as u64);
+                }
+                unsafe { libc::close(fd) };
+            })
+            .map_err(|e| format!("Failed to spawn capture thread: {e}"))?;
+
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+//  Windows 实现 (Npcap)
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(all(target_os = "windows", feature = "npcap"))]
+mod platform {
+    use super::*;
+    use crate::loopback::NPCAP_URL;
+    use std::net::IpAddr;
+    use std::thread;
+
+    /// 按链路层类型剥掉帧头，返回 IP 包部分 (Ethernet 含 802.1Q VLAN、NULL、RAW)
+    fn strip_link_header(linktype: i32, data: &[u8]) -> Option<&[u8]> {
+        match linktype {
+            // ETHERNET
+            1 => {
+                let mut offset = 12;
+                loop {
+                    let ethertype =
+                        u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?);
+                    offset += 2;
+                    match ethertype {
+                        0x8100 | 0x88a8 => offset += 2, // VLAN tag
+                        0x0800 | 0x86dd => return data.get(offset..),
+                        _ => return None,
+                    }
+                }
+            }
+            // NULL (Npcap loopback): 4 字节地址族头
+            0 => data.get(4..),
+            // RAW
+            12 | 14 | 101 => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn start(
+        info: &DeviceInfo,
+        counters: ProtoCounters,
+        filter: Option<PacketFilter>,
+    ) -> Result<(), String> {
+        let devices = pcap::Device::list().map_err(|e| {
+            format!("Failed to list pcap devices: {e}\nPlease install Npcap from: {NPCAP_URL}")
+        })?;
+
+        // Npcap 设备名为 \Device\NPF_{GUID}，按描述或 IP 地址匹配友好名称
+        let lower = info.name.to_lowercase();
+        let device = devices
+            .iter()
+            .find(|d| {
+                d.desc
+                    .as_deref()
+                    .is_some_and(|desc| desc.to_lowercase().contains(&lower))
+            })
+            .or_else(|| {
+                devices.iter().find(|d| {
+                    d.addresses
+                        .iter()
+                        .any(|a| info.addrs.contains(&a.addr.to_string()))
+                })
+            })
+            .cloned()
+            .ok_or_else(|| format!("No Npcap device matches '{}'", info.name))?;
+
+        let local: Vec<IpAddr> = device.addresses.iter().map(|a| a.addr).collect();
+
+        let mut cap = pcap::Capture::from_device(device)
+            .map_err(|e| format!("Failed to open '{}': {e}", info.name))?
+            .promisc(false)
+            .snaplen(96)
+            .timeout(500)
+            .immediate_mode(true)
+            .open()
+            .map_err(|e| {
+                format!(
+                    "Failed to start capture on '{}': {e}\nTry running as administrator.",
+                    info.name
+                )
+            })?;
+        let linktype = cap.get_datalink().0;
+
+        thread::Builder::new()
+            .name("winload-proto".into())
+            .spawn(move || loop {
+                match cap.next_packet() {
+                    Ok(packet) => {
+                        let Some(data) = strip_link_header(linktype, packet.data) else {
+                            continue;
+                        };
+                        let Some((proto, src, dst)) = parse_ip(data) else {
+                            continue;
+                        };
+                        if filter.as_ref().is_some_and(|f| !f.matches(data)) {
+                            continue;
+                        }
+                        let len = packet.header.len as u64;
+                        if local.contains(&dst) {
+                            counters.add(proto, true, len);
+                        } else if local.contains(&src) {
+                            counters.add(proto, false, len);
+                        }
+                    }
+                    Err(pcap::Error::TimeoutExpired) => continue,
+                    Err(_) => break,
+                }
+            })
+            .map_err(|e| format!("Failed to spawn capture thread: {e}"))?;
+
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+//  其他平台 / 未启用 npcap 的 Windows 构建
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(not(any(target_os = "linux", all(target_os = "windows", feature = "npcap"))))]
+mod platform {
+    use super::*;
+
+    pub fn start(
+        _info: &DeviceInfo,
+        _counters: ProtoCounters,
+        _filter: Option<PacketFilter>,
+    ) -> Result<(), String> {
+        #[cfg(target_os = "windows")]
+        {
+            Err("Per-protocol capture requires the 'npcap' feature.".to_string())
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            Err("Per-protocol capture is not available on this platform.".to_string())
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! 会话录制与回放 (--record / --replay)
+//! 录制的是原始累计计数器与时间戳，而不是计算后的速率，
+//! 因此回放时统计引擎会重新计算，即使速率算法改变也能得到一致的结果。
+//!
+//! 文件格式 (UTF-8 文本，字段以 Tab 分隔，设备名可含空格):
+//!
+//! ```text
+//! # winload-record v1
+//! @<elapsed_secs>
+//! <name>\t<bytes_recv>\t<bytes_sent>
+//! ...
+//! ```
+//!
+//! 每个 `@` 行开始一帧，对应一次采集。
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use winload::collector::{DeviceInfo, DeviceKey, Snapshot, TrafficSource};
+
+const HEADER: &str = "# winload-record v1";
+
+/// 每写入多少帧强制刷新一次，程序崩溃时最多丢失这么多帧
+const FLUSH_EVERY_FRAMES: usize = 10;
+
+/// 录制器: 每次采集后追加一帧
+pub struct Recorder {
+    out: BufWriter<File>,
+    frames_since_flush: usize,
+}
+
+impl Recorder {
+    /// 创建 (覆盖) 录制文件并写入文件头
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{HEADER}")?;
+        out.flush()?;
+        Ok(Self {
+            out,
+            frames_since_flush: 0,
+        })
+    }
+
+    /// 写入一帧快照 (按设备名排序，便于比对)
+    pub fn write_frame(&mut self, snapshots: &HashMap<String, Snapshot>) -> io::Result<()> {
+        let Some(elapsed) = snapshots.values().map(|s| s.elapsed_secs).reduce(f64::max) else {
+            return Ok(());
+        };
+        writeln!(self.out, "@{elapsed}")?;
+        let mut names: Vec<&String> = snapshots.keys().collect();
+        names.sort();
+        for name in names {
+            let s = &snapshots[name];
+            writeln!(self.out, "{name}\t{}\t{}", s.bytes_recv, s.bytes_sent)?;
+        }
+        self.frames_since_flush += 1;
+        if self.frames_since_flush >= FLUSH_EVERY_FRAMES {
+            self.out.flush()?;
+            self.frames_since_flush = 0;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.out.flush();
+    }
+}
+
+/// 回放数据源: 每次 [`TrafficSource::collect`] 返回录制文件中的下一帧
+pub struct ReplaySource {
+    frames: Vec<HashMap<String, Snapshot>>,
+    devices: Vec<DeviceInfo>,
+    pos: usize,
+}
+
+impl ReplaySource {
+    /// 读取录制文件；末尾不完整的一行 (崩溃时截断) 会被忽略
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Cannot open {}: {e}", path.display()))?;
+        let mut lines = BufReader::new(file).lines();
+        match lines.next() {
+            Some(Ok(first)) if first.trim() == HEADER => {}
+            _ => return Err(format!("{} is not a winload recording", path.display())),
+        }
+
+        let mut frames: Vec<HashMap<String, Snapshot>> = Vec::new();
+        let mut elapsed = 0.0;
+        for line in lines {
+            let line = line.map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+            if let Some(t) = line.strip_prefix('@') {
+                let Ok(t) = t.trim().parse() else { break };
+                elapsed = t;
+                frames.push(HashMap::new());
+                continue;
+            }
+            let mut fields = line.rsplitn(3, '\t');
+            let (Some(sent), Some(recv), Some(name)) = (fields.next(), fields.next(), fields.next())
+            else {
+                break;
+            };
+            let (Ok(bytes_recv), Ok(bytes_sent)) = (recv.parse(), sent.parse()) else {
+                break;
+            };
+            if let Some(frame) = frames.last_mut() {
+                frame.insert(
+                    name.to_string(),
+                    Snapshot {
+                        elapsed_secs: elapsed,
+                        bytes_recv,
+                        bytes_sent,
+                    },
+                );
+            }
+        }
+        if frames.is_empty() {
+            return Err(format!("{} contains no frames", path.display()));
+        }
+
+        let names: BTreeSet<&String> = frames.iter().flat_map(|f| f.keys()).collect();
+        let devices = names
+            .into_iter()
+            .map(|name| DeviceInfo {
+                name: name.clone(),
+                ..Default::default()
+            })
+            .collect();
+        Ok(Self {
+            frames,
+            devices,
+            pos: 0,
+        })
+    }
+
+    /// 是否已播放完所有帧
+    pub fn finished(&self) -> bool {
+        self.pos >= self.frames.len()
+    }
+}
+
+impl TrafficSource for ReplaySource {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        self.devices.clone()
+    }
+
+    fn collect(&mut self) -> HashMap<DeviceKey, Snapshot> {
+        // 播放完后停在最后一帧: 时间戳不再前进，统计保持不变
+        let idx = self.pos.min(self.frames.len() - 1);
+        self.pos = (self.pos + 1).min(self.frames.len());
+        self.frames[idx]
+            .iter()
+            .map(|(name, s)| (DeviceKey::Name(name.clone()), s.clone()))
+            .collect()
+    }
+
+    fn status(&self) -> Option<String> {
+        self.finished()
+            .then(|| format!("Replay finished ({} frames)", self.frames.len()))
+    }
+}
@@ -0,0 +1,189 @@
+//! 远程主机数据源 (--remote)
+//! 通过 ssh 在远端循环读取 `/proc/uptime` 与 `/proc/net/dev`，解析为 [`Snapshot`]。
+//!
+//! - 只支持 Linux 远端 (依赖 /proc)，本地只需要 `ssh` 命令
+//! - 使用 `BatchMode=yes`，需要事先配置免密登录 (密钥 / agent)，避免密码提示破坏 TUI
+//! - 时间戳取远端 `/proc/uptime`，速率不受网络延迟抖动影响
+//! - 连接断开后后台线程自动重连，期间 [`TrafficSource::status`] 返回提示文本
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use winload::collector::{DeviceInfo, DeviceKey, Snapshot, TrafficSource};
+
+/// 每帧数据之间的分隔行
+const FRAME_END: &str = "@@winload@@";
+
+/// 断线后重连前的等待时间
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// 后台线程与主线程共享的状态
+#[derive(Default)]
+struct Shared {
+    /// 最近一帧解析结果
+    latest: HashMap<String, Snapshot>,
+    /// 当前是否处于断线重连中
+    reconnecting: bool,
+}
+
+/// 远程主机数据源
+pub struct RemoteSource {
+    /// ssh 目标 (user@host)
+    pub host: String,
+    devices: Vec<DeviceInfo>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl TrafficSource for RemoteSource {
+    fn devices(&self) -> Vec<DeviceInfo> {
+        self.devices.clone()
+    }
+
+    fn collect(&mut self) -> HashMap<DeviceKey, Snapshot> {
+        let Ok(shared) = self.shared.lock() else {
+            return HashMap::new();
+        };
+        shared
+            .latest
+            .iter()
+            .map(|(name, s)| (DeviceKey::Name(name.clone()), s.clone()))
+            .collect()
+    }
+
+    fn status(&self) -> Option<String> {
+        let reconnecting = self.shared.lock().map(|s| s.reconnecting).unwrap_or(false);
+        reconnecting.then(|| format!("Connection to {} lost, reconnecting...", self.host))
+    }
+}
+
+/// 连接远端主机并开始按 `interval_ms` 间隔拉取计数器
+///
+/// 首次连接同步进行，失败时返回包含 ssh 错误输出的说明；之后的断线由后台线程自动重连。
+pub fn connect(host: &str, interval_ms: u64) -> Result<RemoteSource, String> {
+    let output = ssh_command(host, "cat /proc/uptime /proc/net/dev")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run ssh: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ssh {host} failed ({}):\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let first = parse_frame(text.lines())
+        .ok_or_else(|| format!("Unexpected output from {host}: is it a Linux host with /proc/net/dev?"))?;
+
+    let mut devices: Vec<DeviceInfo> = first
+        .keys()
+        .map(|name| DeviceInfo {
+            name: name.clone(),
+            ..Default::default()
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let shared = Arc::new(Mutex::new(Shared {
+        latest: first,
+        reconnecting: false,
+    }));
+    let thread_shared = Arc::clone(&shared);
+    let thread_host = host.to_string();
+    thread::Builder::new()
+        .name("winload-remote".into())
+        .spawn(move || stream_loop(&thread_host, interval_ms, &thread_shared))
+        .map_err(|e| format!("Failed to spawn remote thread: {e}"))?;
+
+    Ok(RemoteSource {
+        host: host.to_string(),
+        devices,
+        shared,
+    })
+}
+
+/// 构造 ssh 命令: 禁止交互式密码提示，并尽快发现断线
+fn ssh_command(host: &str, remote_cmd: &str) -> Command {
+    let mut cmd = Command::new("ssh");
+    cmd.args([
+        "-o",
+        "BatchMode=yes",
+        "-o",
+        "ConnectTimeout=5",
+        "-o",
+        "ServerAliveInterval=5",
+        "-o",
+        "ServerAliveCountMax=2",
+        host,
+        remote_cmd,
+    ]);
+    cmd
+}
+
+/// 后台线程: 保持一个长连接持续读取帧，断开后等待并重连
+fn stream_loop(host: &str, interval_ms: u64, shared: &Mutex<Shared>) {
+    let script = format!(
+        "while :; do cat /proc/uptime /proc/net/dev; echo {FRAME_END}; sleep {:.3}; done",
+        interval_ms as f64 / 1000.0
+    );
+    loop {
+        if let Ok(mut child) = ssh_command(host, &script)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            if let Some(stdout) = child.stdout.take() {
+                let mut frame = Vec::new();
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else { break };
+                    if line != FRAME_END {
+                        frame.push(line);
+                        continue;
+                    }
+                    if let Some(snapshots) = parse_frame(frame.iter().map(String::as_str)) {
+                        if let Ok(mut s) = shared.lock() {
+                            s.latest = snapshots;
+                            s.reconnecting = false;
+                        }
+                    }
+                    frame.clear();
+                }
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Ok(mut s) = shared.lock() {
+            s.reconnecting = true;
+        }
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+/// 解析一帧 `/proc/uptime` + `/proc/net/dev` 输出
+fn parse_frame<'a>(mut lines: impl Iterator<Item = &'a str>) -> Option<HashMap<String, Snapshot>> {
+    let uptime: f64 = lines.next()?.split_whitespace().next()?.parse().ok()?;
+    let snapshots: HashMap<String, Snapshot> = lines
+        .filter_map(|line| {
+            // "  eth0: rx_bytes rx_packets ... (8 个接收字段) tx_bytes ..."
+            let (name, counters) = line.split_once(':')?;
+            let fields: Vec<u64> = counters
+                .split_whitespace()
+                .map(|f| f.parse().ok())
+                .collect::<Option<_>>()?;
+            Some((
+                name.trim().to_string(),
+                Snapshot {
+                    elapsed_secs: uptime,
+                    bytes_recv: *fields.first()?,
+                    bytes_sent: *fields.get(8)?,
+                },
+            ))
+        })
+        .collect();
+    (!snapshots.is_empty()).then_some(snapshots)
+}
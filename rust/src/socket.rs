@@ -0,0 +1,101 @@
+//! 本地指标套接字 (--socket)
+//! 每次采样后把各网卡的统计以一行 JSON (NDJSON) 广播给所有已连接的订阅者。
+//!
+//! - Unix: 监听 Unix domain socket，退出时删除套接字文件
+//! - Windows: 暂不支持 (命名管道尚未实现)，[`MetricsSocket::bind`] 返回错误
+//!
+//! 所有读写均为非阻塞: 没有订阅者或订阅者读得慢都不会拖慢主循环，
+//! 写不完整的订阅者会被直接断开，避免后续数据错位。
+
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use winload::collector::json_string;
+use winload::stats::StatisticsEngine;
+
+/// 生成一行 NDJSON: `{"ts":..,"devices":[{"name":..,"in":{..},"out":{..}}, ..]}`
+pub fn metrics_line<'a>(devices: impl Iterator<Item = (&'a str, &'a StatisticsEngine)>) -> String {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64());
+    let devices: Vec<String> = devices
+        .map(|(name, engine)| {
+            format!(
+                "{{\"name\":{},\"in\":{},\"out\":{}}}",
+                json_string(name),
+                engine.incoming.to_json(),
+                engine.outgoing.to_json(),
+            )
+        })
+        .collect();
+    format!("{{\"ts\":{ts:.3},\"devices\":[{}]}}\n", devices.join(","))
+}
+
+#[cfg(unix)]
+pub use unix::MetricsSocket;
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::io::Write;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::PathBuf;
+
+    /// 广播指标的 Unix 套接字
+    pub struct MetricsSocket {
+        path: PathBuf,
+        listener: UnixListener,
+        clients: Vec<UnixStream>,
+    }
+
+    impl MetricsSocket {
+        /// 在 `path` 上监听；若存在上次遗留的套接字文件则先删除
+        pub fn bind(path: &Path) -> io::Result<Self> {
+            if path.exists() && UnixStream::connect(path).is_err() {
+                std::fs::remove_file(path)?;
+            }
+            let listener = UnixListener::bind(path)?;
+            listener.set_nonblocking(true)?;
+            Ok(Self {
+                path: path.to_path_buf(),
+                listener,
+                clients: Vec::new(),
+            })
+        }
+
+        /// 接受新订阅者并向所有订阅者发送一行
+        pub fn broadcast(&mut self, line: &str) {
+            while let Ok((stream, _)) = self.listener.accept() {
+                if stream.set_nonblocking(true).is_ok() {
+                    self.clients.push(stream);
+                }
+            }
+            let bytes = line.as_bytes();
+            self.clients
+                .retain_mut(|c| matches!(c.write(bytes), Ok(n) if n == bytes.len()));
+        }
+    }
+
+    impl Drop for MetricsSocket {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+/// 广播指标的套接字 (当前平台不支持)
+pub struct MetricsSocket;
+
+#[cfg(not(unix))]
+impl MetricsSocket {
+    pub fn bind(_path: &Path) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "--socket is only supported on Unix for now",
+        ))
+    }
+
+    pub fn broadcast(&mut self, _line: &str) {}
+}
@@ -0,0 +1,150 @@
+//! 跨次运行的小型状态持久化
+//! 记录上次查看的网卡名 (下次启动时优先选中)、各网卡的速率历史 (图形重启后接续显示)
+//! 与收藏的网卡 (* 键标记)。
+//!
+//! 状态文件位置 (历史记录 / 收藏为同目录下的 `history` / `favorites`):
+//! - Linux / macOS: `$XDG_STATE_HOME/winload/last_device`，
+//!   未设置时为 `~/.local/state/winload/last_device`
+//! - Windows: `%LOCALAPPDATA%\winload\last_device`
+//!
+//! 读写均为尽力而为: 失败时静默忽略，不影响正常监控。
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const LAST_DEVICE_FILE: &str = "last_device";
+const HISTORY_FILE: &str = "history";
+const FAVORITES_FILE: &str = "favorites";
+
+/// 单个网卡保存的速率历史
+pub struct SavedHistory {
+    pub name: String,
+    /// 保存时的采样间隔 (毫秒)，间隔不同的历史不能直接接续
+    pub interval_ms: u64,
+    /// 收方向速率 (front = 最新值，同 `StatisticsEngine::incoming_history`)
+    pub incoming: Vec<f64>,
+    /// 发方向速率
+    pub outgoing: Vec<f64>,
+}
+
+/// winload 的状态目录，无法确定时返回 None
+pub fn state_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("LOCALAPPDATA").map(|d| PathBuf::from(d).join("winload"))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        std::env::var_os("XDG_STATE_HOME")
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local").join("state"))
+            })
+            .map(|d| d.join("winload"))
+    }
+}
+
+/// 读取上次查看的网卡名
+pub fn load_last_device() -> Option<String> {
+    let path = state_dir()?.join(LAST_DEVICE_FILE);
+    let name = fs::read_to_string(path).ok()?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// 记录当前查看的网卡名
+pub fn save_last_device(name: &str) {
+    let Some(dir) = state_dir() else {
+        return;
+    };
+    if fs::create_dir_all(&dir).is_ok() {
+        let _ = fs::write(dir.join(LAST_DEVICE_FILE), name);
+    }
+}
+
+/// 读取收藏的网卡名 (每行一个)
+pub fn load_favorites() -> Vec<String> {
+    let Some(text) = state_dir().and_then(|d| fs::read_to_string(d.join(FAVORITES_FILE)).ok()) else {
+        return Vec::new();
+    };
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 保存收藏的网卡名，覆盖上次的记录
+pub fn save_favorites(names: &[String]) {
+    let Some(dir) = state_dir() else {
+        return;
+    };
+    let mut text = names.join("\n");
+    text.push('\n');
+    if fs::create_dir_all(&dir).is_ok() {
+        let _ = fs::write(dir.join(FAVORITES_FILE), text);
+    }
+}
+
+/// 读取上次运行保存的速率历史，返回 (距保存时刻的时长, 各网卡历史)
+///
+/// 文件格式: 首行为保存时刻 (Unix 秒)，之后每行一个网卡
+/// `name<TAB>interval_ms<TAB>收速率,...<TAB>发速率,...`。
+pub fn load_history() -> Option<(Duration, Vec<SavedHistory>)> {
+    let text = fs::read_to_string(state_dir()?.join(HISTORY_FILE)).ok()?;
+    let mut lines = text.lines();
+    let saved_at = UNIX_EPOCH + Duration::from_secs(lines.next()?.trim().parse().ok()?);
+    // 系统时钟回拨时视为刚保存
+    let age = SystemTime::now().duration_since(saved_at).unwrap_or_default();
+    let parse_rates = |s: &str| -> Option<Vec<f64>> {
+        s.split(',')
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse::<f64>().ok().filter(|r| r.is_finite() && *r >= 0.0))
+            .collect()
+    };
+    let devices = lines
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some(SavedHistory {
+                name: fields.next()?.to_string(),
+                interval_ms: fields.next()?.parse().ok()?,
+                incoming: parse_rates(fields.next()?)?,
+                outgoing: parse_rates(fields.next()?)?,
+            })
+        })
+        .collect();
+    Some((age, devices))
+}
+
+/// 保存各网卡的速率历史，覆盖上次的记录
+pub fn save_history(devices: &[SavedHistory]) {
+    let Some(dir) = state_dir() else {
+        return;
+    };
+    let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+        return;
+    };
+    // 速率取整到 B/s，保持文件小巧
+    let join = |rates: &[f64]| {
+        rates
+            .iter()
+            .map(|r| format!("{r:.0}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let mut text = format!("{}\n", now.as_secs());
+    for d in devices {
+        text.push_str(&format!(
+            "{}\t{}\t{}\t{}\n",
+            d.name,
+            d.interval_ms,
+            join(&d.incoming),
+            join(&d.outgoing)
+        ));
+    }
+    if fs::create_dir_all(&dir).is_ok() {
+        let _ = fs::write(dir.join(HISTORY_FILE), text);
+    }
+}
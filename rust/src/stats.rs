@@ -7,6 +7,7 @@ use crate::collector::Snapshot;
 
 /// 某一方向（收/发）的统计结果
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TrafficStats {
     /// 当前速率 (bytes/s)
     pub current: f64,
@@ -20,6 +21,20 @@ pub struct TrafficStats {
     pub total: u64,
 }
 
+impl TrafficStats {
+    /// 以 JSON 对象形式输出 (不依赖 serde)，速率单位为 bytes/s
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"current\":{:.1},\"average\":{:.1},\"minimum\":{:.1},\"maximum\":{:.1},\"total\":{}}}",
+            self.current,
+            self.average,
+            if self.minimum.is_finite() { self.minimum } else { 0.0 },
+            self.maximum,
+            self.total,
+        )
+    }
+}
+
 impl Default for TrafficStats {
     fn default() -> Self {
         Self {
@@ -32,13 +47,172 @@ impl Default for TrafficStats {
     }
 }
 
+/// 统计引擎状态的只读快照，供自定义显示或序列化使用
+///
+/// 启用 `serde` feature 后可直接序列化为 JSON 等格式。
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EngineSnapshot {
+    /// 收方向统计
+    pub incoming: TrafficStats,
+    /// 发方向统计
+    pub outgoing: TrafficStats,
+    /// 收方向速率历史 (下标 0 = 最新值，bytes/s)
+    pub incoming_history: Vec<f64>,
+    /// 发方向速率历史
+    pub outgoing_history: Vec<f64>,
+    /// 速率历史各样本的时刻 (秒，相对本次运行的第一个样本；上次运行恢复的样本为负值)
+    pub history_secs: Vec<f64>,
+}
+
+/// 峰值记录 (spike log) 中的一次突发
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spike {
+    /// 本次突发中的最高速率 (bytes/s)
+    pub speed: f64,
+    /// 出现最高速率的时刻 (自本次运行第一个样本起的秒数)
+    pub at_secs: f64,
+    /// 本次突发最后一个入选样本的时刻，用于判断后续样本是否属于同一次突发
+    last_seen: f64,
+}
+
+/// 同一次突发的判定间隔 (秒): 距上一个入选样本不超过该值的高速率样本合并为一条记录
+const SPIKE_MERGE_SECS: f64 = 10.0;
+
+/// 记录一个速率样本，维护按速率从高到低排列的前 `cap` 个突发
+///
+/// 只有能进入列表的样本才会延续突发: 速率回落到榜单以下超过 [`SPIKE_MERGE_SECS`] 即视为结束，
+/// 因此一次持续的高流量只占一条记录。
+fn record_spike(spikes: &mut Vec<Spike>, cap: usize, speed: f64, at: f64) {
+    if cap == 0 || speed <= 0.0 {
+        return;
+    }
+    if spikes.len() >= cap && spikes.last().is_some_and(|s| speed <= s.speed) {
+        return;
+    }
+    if let Some(s) = spikes.iter_mut().find(|s| at - s.last_seen <= SPIKE_MERGE_SECS) {
+        s.last_seen = at;
+        if speed > s.speed {
+            s.speed = speed;
+            s.at_secs = at;
+        }
+    } else {
+        if spikes.len() >= cap {
+            spikes.pop();
+        }
+        spikes.push(Spike {
+            speed,
+            at_secs: at,
+            last_seen: at,
+        });
+    }
+    spikes.sort_by(|a, b| b.speed.total_cmp(&a.speed));
+}
+
+/// 滑动窗口最大值: 追加 `at` 时刻的速率并移除窗口外的样本
+///
+/// `window` 按速率单调递减保存 (时刻, 速率)，front 即窗口内的最大值；
+/// 被更新更大的样本压住的旧样本不可能再成为最大值，直接丢弃，内存不随窗口长度增长。
+fn push_window_max(window: &mut VecDeque<(f64, f64)>, secs: f64, speed: f64, at: f64) {
+    while window.back().is_some_and(|&(_, v)| v <= speed) {
+        window.pop_back();
+    }
+    window.push_back((at, speed));
+    while window.front().is_some_and(|&(t, _)| t <= at - secs) {
+        window.pop_front();
+    }
+}
+
+/// Avg 的计算方式
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum AvgMethod {
+    /// 简单平均: 整个平均窗口内的总字节数 / 时长，平稳
+    #[default]
+    Sma,
+    /// 指数移动平均: 每个样本按 `alpha` 混入当前速率，对近期变化反应更快
+    Ema {
+        /// 平滑系数 (0, 1]，越大越偏重最新样本
+        alpha: f64,
+    },
+}
+
+/// 当前速率的计算输入，见 [`StatisticsEngine::rate_inputs`]
+#[derive(Clone, Debug)]
+pub struct RateInputs {
+    /// 窗口起点的快照
+    pub prev: Snapshot,
+    /// 最新快照
+    pub latest: Snapshot,
+    /// 收方向字节增量 (计数器重置 / 回绕时为负，速率按 0 计)
+    pub delta_recv: i128,
+    /// 发方向字节增量
+    pub delta_sent: i128,
+    /// 两个快照之间的实际时长 (秒)
+    pub dt: f64,
+}
+
+/// 当前速率的时间窗口 (秒)
+const CURRENT_WINDOW_SECS: f64 = 1.0;
+
+/// 速率 / 累计历史保留的样本数
+pub const HISTORY_LEN: usize = 1024;
+
+/// 平均窗口锚点的最大数量
+///
+/// 平均速率 = (最新计数 - 窗口起点计数) / 时长，只需要窗口起点附近的一个快照，
+/// 因此按窗口长度均匀抽样保留锚点即可，窗口起点的误差不超过窗口长度的 1/AVG_RING_CAP。
+const AVG_RING_CAP: usize = 1024;
+
 /// 统计引擎：为单个网卡维护收发两个方向的统计数据
 pub struct StatisticsEngine {
+    /// 最近的快照 (只保留计算当前速率所需的 ~2 个窗口)
     samples: VecDeque<Snapshot>,
-    second_window: usize,
-    max_samples: usize,
     sample_count: usize,
 
+    /// 平均窗口的锚点快照: 按 `avg_window_secs / AVG_RING_CAP` 的间隔抽样，
+    /// 内存上限固定，与 --average 窗口长度和采样间隔无关
+    avg_ring: VecDeque<Snapshot>,
+    /// 平均窗口长度 (秒)
+    avg_window_secs: f64,
+
+    /// 中值滤波窗口 (0 = 关闭)，仅作用于显示的当前速率
+    median_window: usize,
+    /// 最近若干个未滤波的当前速率 (收, 发)，供中值滤波使用
+    raw_current: VecDeque<(f64, f64)>,
+
+    /// Avg 的计算方式
+    avg_method: AvgMethod,
+
+    /// 滚动窗口总量的窗口长度 (秒)，None = 关闭
+    window_secs: Option<f64>,
+    /// 窗口内每次采样的字节增量 (快照 elapsed_secs, 收, 发)，front = 最旧
+    window_deltas: VecDeque<(f64, u64, u64)>,
+    /// 窗口内增量之和 (收, 发)
+    window_sums: (u64, u64),
+
+    /// 冻结图形: 不再追加速率/累计历史，统计数值照常更新
+    history_frozen: bool,
+
+    /// 峰值记录保留的突发数 (0 = 关闭)
+    spike_cap: usize,
+    /// 收方向速率最高的若干次突发 (从高到低)
+    incoming_spikes: Vec<Spike>,
+    /// 发方向速率最高的若干次突发
+    outgoing_spikes: Vec<Spike>,
+    /// 本次运行第一个快照的 elapsed_secs，峰值时刻以此为起点
+    first_elapsed: Option<f64>,
+
+    /// 近期峰值的窗口长度 (秒)，None = 关闭
+    recent_max_secs: Option<f64>,
+    /// 收 / 发方向窗口内的候选峰值 (时刻, 速率)，见 [`push_window_max`]
+    recent_max_in: VecDeque<(f64, f64)>,
+    recent_max_out: VecDeque<(f64, f64)>,
+
+    /// 收发速率同时降为 0 的起始时刻 (快照 elapsed_secs)，有流量时为 None
+    idle_since: Option<f64>,
+    /// 最新快照的 elapsed_secs
+    last_elapsed: f64,
+
     /// 收方向统计
     pub incoming: TrafficStats,
     /// 发方向统计
@@ -48,33 +222,162 @@ pub struct StatisticsEngine {
     pub incoming_history: VecDeque<f64>,
     /// 发方向速率历史
     pub outgoing_history: VecDeque<f64>,
+    /// 速率历史各样本的时刻 (与速率历史一一对应，front = 最新)，相对 `first_elapsed` 的秒数
+    history_secs: VecDeque<f64>,
+    /// 速率历史末尾 (最旧一端) 来自上次运行的样本数
+    restored: usize,
+    /// 设定的采样间隔 (秒)，用于推算恢复样本的时刻
+    interval_secs: f64,
+
+    /// 本次运行开始时的累计字节数 (收, 发)，用作累计历史的基准
+    session_base: Option<(u64, u64)>,
+    /// 收方向本次运行累计字节数历史 (front = 最新值，单调递增)
+    pub incoming_total_history: VecDeque<u64>,
+    /// 发方向本次运行累计字节数历史
+    pub outgoing_total_history: VecDeque<u64>,
 }
 
 impl StatisticsEngine {
+    /// 创建统计引擎
+    ///
+    /// - `refresh_interval_ms`: 采样间隔（毫秒），用于估算平均窗口需保留的样本数
+    /// - `average_window_sec`:  平均速率的窗口长度（秒）
     pub fn new(refresh_interval_ms: u64, average_window_sec: u64) -> Self {
-        let second_window = (1000u64 / refresh_interval_ms).max(1) as usize;
-        let max_samples =
-            ((1000u64 / refresh_interval_ms) * average_window_sec).max(600) as usize;
+        let refresh_interval_ms = refresh_interval_ms.max(1);
+        // 窗口至少覆盖 600 个采样间隔
+        let window_samples = ((1000u64 / refresh_interval_ms) * average_window_sec).max(600);
+        let avg_window_secs = window_samples as f64 * refresh_interval_ms as f64 / 1000.0;
 
         Self {
-            samples: VecDeque::with_capacity(max_samples),
-            second_window,
-            max_samples,
+            samples: VecDeque::new(),
             sample_count: 0,
+            avg_ring: VecDeque::with_capacity(AVG_RING_CAP + 1),
+            avg_window_secs,
+            median_window: 0,
+            raw_current: VecDeque::new(),
+            avg_method: AvgMethod::Sma,
+            window_secs: None,
+            window_deltas: VecDeque::new(),
+            window_sums: (0, 0),
+            history_frozen: false,
+            spike_cap: 0,
+            incoming_spikes: Vec::new(),
+            outgoing_spikes: Vec::new(),
+            first_elapsed: None,
+            recent_max_secs: None,
+            recent_max_in: VecDeque::new(),
+            recent_max_out: VecDeque::new(),
+            idle_since: None,
+            last_elapsed: 0.0,
             incoming: TrafficStats::default(),
             outgoing: TrafficStats::default(),
-            incoming_history: VecDeque::with_capacity(1024),
-            outgoing_history: VecDeque::with_capacity(1024),
+            incoming_history: VecDeque::with_capacity(HISTORY_LEN),
+            outgoing_history: VecDeque::with_capacity(HISTORY_LEN),
+            history_secs: VecDeque::with_capacity(HISTORY_LEN),
+            restored: 0,
+            interval_secs: refresh_interval_ms as f64 / 1000.0,
+            session_base: None,
+            incoming_total_history: VecDeque::with_capacity(HISTORY_LEN),
+            outgoing_total_history: VecDeque::with_capacity(HISTORY_LEN),
         }
     }
 
+    /// 设置当前速率的中值滤波窗口 (如 3 或 5 个样本)，0 表示关闭
+    ///
+    /// 只平滑 `current` 读数，用于剔除单拍计数器抖动造成的假尖峰；
+    /// 图形历史和 Min/Max 仍使用原始值。
+    pub fn set_median_window(&mut self, window: usize) {
+        self.median_window = window;
+        self.raw_current = VecDeque::with_capacity(window);
+    }
+
+    /// 设置 Avg 的计算方式 (默认 [`AvgMethod::Sma`])
+    ///
+    /// EMA 按样本更新，因此同一个 `alpha` 在不同采样间隔下对应的时间常数不同。
+    pub fn set_avg_method(&mut self, method: AvgMethod) {
+        self.avg_method = method;
+    }
+
+    /// 开启滚动窗口总量: 统计最近 `secs` 秒内的收发字节数 (见 [`Self::window_total`])
+    pub fn set_window_total(&mut self, secs: u64) {
+        self.window_secs = Some(secs as f64);
+        self.window_deltas.clear();
+        self.window_sums = (0, 0);
+    }
+
+    /// 开启峰值记录: 每个方向保留速率最高的 `n` 次突发 (见 [`Self::spikes`])
+    pub fn set_spike_log(&mut self, n: usize) {
+        self.spike_cap = n;
+        self.incoming_spikes.truncate(n);
+        self.outgoing_spikes.truncate(n);
+    }
+
+    /// 开启近期峰值: 最近 `secs` 秒内的最高速率 (见 [`Self::recent_max`])
+    pub fn set_recent_max(&mut self, secs: u64) {
+        self.recent_max_secs = Some(secs as f64);
+        self.recent_max_in.clear();
+        self.recent_max_out.clear();
+    }
+
+    /// 最近窗口内的最高速率 (收, 发)；与只增不减的 Max 不同，突发移出窗口后回落。未开启时返回 None
+    pub fn recent_max(&self) -> Option<(f64, f64)> {
+        self.recent_max_secs?;
+        let peak = |w: &VecDeque<(f64, f64)>| w.front().map_or(0.0, |&(_, v)| v);
+        Some((peak(&self.recent_max_in), peak(&self.recent_max_out)))
+    }
+
+    /// 收 / 发方向速率最高的若干次突发，按速率从高到低排列
+    pub fn spikes(&self) -> (&[Spike], &[Spike]) {
+        (&self.incoming_spikes, &self.outgoing_spikes)
+    }
+
+    /// 实际使用的平均窗口长度 (秒)，可能因最少采样数要求而长于 --average
+    pub fn avg_window_secs(&self) -> f64 {
+        self.avg_window_secs
+    }
+
+    /// 最近窗口内的收发字节数 (收, 发)；未开启时返回 None
+    pub fn window_total(&self) -> Option<(u64, u64)> {
+        self.window_secs.map(|_| self.window_sums)
+    }
+
     /// 喂入新的采样快照，重新计算统计
+    ///
+    /// 速率均按快照中实际记录的 `elapsed_secs` 差值计算，而不是假定的刷新间隔，
+    /// 因此主循环绘制变慢或调度抖动时不会导致速率虚高/虚低。
     pub fn update(&mut self, snapshot: Snapshot) {
+        let base = *self
+            .session_base
+            .get_or_insert((snapshot.bytes_recv, snapshot.bytes_sent));
+        let first_elapsed = *self.first_elapsed.get_or_insert(snapshot.elapsed_secs);
         self.samples.push_back(snapshot);
-        if self.samples.len() > self.max_samples {
+        self.sample_count += 1;
+
+        // 近期快照: 只保留 2 个当前速率窗口 (至少 2 个快照)
+        let newest = self.samples.back().map_or(0.0, |s| s.elapsed_secs);
+        while self.samples.len() > 2
+            && newest - self.samples[0].elapsed_secs > CURRENT_WINDOW_SECS * 2.0
+        {
             self.samples.pop_front();
         }
-        self.sample_count += 1;
+
+        // 平均窗口锚点: 按固定间隔抽样，移出比窗口起点更旧的锚点
+        let stride = self.avg_window_secs / AVG_RING_CAP as f64;
+        if self
+            .avg_ring
+            .back()
+            .is_none_or(|s| newest - s.elapsed_secs >= stride)
+        {
+            if let Some(s) = self.samples.back() {
+                self.avg_ring.push_back(s.clone());
+            }
+        }
+        while self.avg_ring.len() > 1
+            && (self.avg_ring.len() > AVG_RING_CAP
+                || newest - self.avg_ring[1].elapsed_secs >= self.avg_window_secs)
+        {
+            self.avg_ring.pop_front();
+        }
 
         let n = self.samples.len();
         if n < 2 {
@@ -83,40 +386,93 @@ impl StatisticsEngine {
 
         let latest = &self.samples[n - 1];
 
-        // ── 当前速率 (最近 ~1s 的窗口) ──
-        let sec_idx = if n > self.second_window + 1 {
-            n - 1 - self.second_window
-        } else {
-            0
-        };
-        let older = &self.samples[sec_idx];
-        let dt = latest.elapsed_secs - older.elapsed_secs;
+        // ── 滚动窗口总量: 累加本次增量，移出窗口外的旧增量 ──
+        if let Some(window) = self.window_secs {
+            let prev = &self.samples[n - 2];
+            let d_in = latest.bytes_recv.saturating_sub(prev.bytes_recv);
+            let d_out = latest.bytes_sent.saturating_sub(prev.bytes_sent);
+            self.window_deltas.push_back((latest.elapsed_secs, d_in, d_out));
+            self.window_sums.0 += d_in;
+            self.window_sums.1 += d_out;
+            while let Some(&(t, old_in, old_out)) = self.window_deltas.front() {
+                if latest.elapsed_secs - t < window {
+                    break;
+                }
+                self.window_sums.0 -= old_in;
+                self.window_sums.1 -= old_out;
+                self.window_deltas.pop_front();
+            }
+        }
 
-        if dt > 0.0 {
-            self.incoming.current =
-                ((latest.bytes_recv as f64 - older.bytes_recv as f64) / dt).max(0.0);
-            self.outgoing.current =
-                ((latest.bytes_sent as f64 - older.bytes_sent as f64) / dt).max(0.0);
+        // ── 当前速率 (按实际时间回溯 ~1s 的窗口) ──
+        if let Some(inputs) = self.rate_inputs() {
+            if inputs.dt > 0.0 {
+                self.incoming.current = (inputs.delta_recv as f64 / inputs.dt).max(0.0);
+                self.outgoing.current = (inputs.delta_sent as f64 / inputs.dt).max(0.0);
+            }
         }
 
-        // 记录到历史 (graph 用)
-        if self.incoming_history.len() >= 1024 {
-            self.incoming_history.pop_back();
+        // ── 峰值记录 (不受图形冻结影响) ──
+        let at = latest.elapsed_secs - first_elapsed;
+        record_spike(&mut self.incoming_spikes, self.spike_cap, self.incoming.current, at);
+        record_spike(&mut self.outgoing_spikes, self.spike_cap, self.outgoing.current, at);
+        if let Some(secs) = self.recent_max_secs {
+            push_window_max(&mut self.recent_max_in, secs, self.incoming.current, at);
+            push_window_max(&mut self.recent_max_out, secs, self.outgoing.current, at);
         }
-        if self.outgoing_history.len() >= 1024 {
-            self.outgoing_history.pop_back();
+
+        // 记录到历史 (graph 用)，冻结图形时跳过
+        if !self.history_frozen {
+            if self.incoming_history.len() >= HISTORY_LEN {
+                self.incoming_history.pop_back();
+                self.history_secs.pop_back();
+                self.restored = self.restored.saturating_sub(1);
+            }
+            if self.outgoing_history.len() >= HISTORY_LEN {
+                self.outgoing_history.pop_back();
+            }
+            self.incoming_history.push_front(self.incoming.current);
+            self.outgoing_history.push_front(self.outgoing.current);
+            self.history_secs.push_front(at);
+
+            // 累计历史: 相对本次运行起点 (计数器回绕/重置时不回退)
+            let prev_in = self.incoming_total_history.front().copied().unwrap_or(0);
+            let prev_out = self.outgoing_total_history.front().copied().unwrap_or(0);
+            if self.incoming_total_history.len() >= HISTORY_LEN {
+                self.incoming_total_history.pop_back();
+            }
+            if self.outgoing_total_history.len() >= HISTORY_LEN {
+                self.outgoing_total_history.pop_back();
+            }
+            self.incoming_total_history
+                .push_front(latest.bytes_recv.saturating_sub(base.0).max(prev_in));
+            self.outgoing_total_history
+                .push_front(latest.bytes_sent.saturating_sub(base.1).max(prev_out));
         }
-        self.incoming_history.push_front(self.incoming.current);
-        self.outgoing_history.push_front(self.outgoing.current);
 
-        // ── 平均速率 (整个窗口) ──
-        let oldest = &self.samples[0];
-        let dt_all = latest.elapsed_secs - oldest.elapsed_secs;
-        if dt_all > 0.0 {
-            self.incoming.average =
-                ((latest.bytes_recv as f64 - oldest.bytes_recv as f64) / dt_all).max(0.0);
-            self.outgoing.average =
-                ((latest.bytes_sent as f64 - oldest.bytes_sent as f64) / dt_all).max(0.0);
+        // ── 平均速率 ──
+        match self.avg_method {
+            // 整个窗口的总字节数 / 时长
+            AvgMethod::Sma => {
+                let oldest = &self.avg_ring[0];
+                let dt_all = latest.elapsed_secs - oldest.elapsed_secs;
+                if dt_all > 0.0 {
+                    self.incoming.average =
+                        ((latest.bytes_recv as f64 - oldest.bytes_recv as f64) / dt_all).max(0.0);
+                    self.outgoing.average =
+                        ((latest.bytes_sent as f64 - oldest.bytes_sent as f64) / dt_all).max(0.0);
+                }
+            }
+            // 第一个有效样本直接作为初值，之后按 alpha 混入当前速率
+            AvgMethod::Ema { alpha } => {
+                if self.sample_count == 2 {
+                    self.incoming.average = self.incoming.current;
+                    self.outgoing.average = self.outgoing.current;
+                } else {
+                    self.incoming.average += alpha * (self.incoming.current - self.incoming.average);
+                    self.outgoing.average += alpha * (self.outgoing.current - self.outgoing.average);
+                }
+            }
         }
 
         // ── Min / Max ──
@@ -135,55 +491,543 @@ impl StatisticsEngine {
             self.outgoing.minimum = 0.0;
         }
 
+        // ── 中值滤波 (仅显示用) ──
+        if self.median_window > 1 {
+            if self.raw_current.len() >= self.median_window {
+                self.raw_current.pop_front();
+            }
+            self.raw_current
+                .push_back((self.incoming.current, self.outgoing.current));
+            self.incoming.current = median(self.raw_current.iter().map(|c| c.0));
+            self.outgoing.current = median(self.raw_current.iter().map(|c| c.1));
+        }
+
+        // ── 静默计时 ──
+        self.last_elapsed = latest.elapsed_secs;
+        if self.incoming.current > 0.0 || self.outgoing.current > 0.0 {
+            self.idle_since = None;
+        } else if self.idle_since.is_none() {
+            self.idle_since = Some(latest.elapsed_secs);
+        }
+
         // ── Total ──
         self.incoming.total = latest.bytes_recv;
         self.outgoing.total = latest.bytes_sent;
     }
+
+    /// 平均速率中收方向所占比例 (0.0 ~ 1.0)；收发都为 0 时返回 None
+    pub fn in_share(&self) -> Option<f64> {
+        let sum = self.incoming.average + self.outgoing.average;
+        (sum > 0.0).then(|| self.incoming.average / sum)
+    }
+
+    /// 当前速率的计算输入: 窗口两端的原始计数、增量与时长 (调试浮层用)
+    ///
+    /// 快照不足 2 个时返回 None。
+    pub fn rate_inputs(&self) -> Option<RateInputs> {
+        let latest = self.samples.back()?;
+        // 允许 5% 的抖动，避免因采样略早于 1s 而多回溯一个样本
+        let older = self
+            .samples
+            .iter()
+            .rev()
+            .skip(1)
+            .find(|s| latest.elapsed_secs - s.elapsed_secs >= CURRENT_WINDOW_SECS * 0.95)
+            .or_else(|| self.samples.front().filter(|_| self.samples.len() >= 2))?;
+        Some(RateInputs {
+            prev: older.clone(),
+            latest: latest.clone(),
+            delta_recv: latest.bytes_recv as i128 - older.bytes_recv as i128,
+            delta_sent: latest.bytes_sent as i128 - older.bytes_sent as i128,
+            dt: latest.elapsed_secs - older.elapsed_secs,
+        })
+    }
+
+    /// 本次运行以来的收发字节数 (收, 发)
+    pub fn session_totals(&self) -> (u64, u64) {
+        let (base_in, base_out) = self.session_base.unwrap_or_default();
+        (
+            self.incoming.total.saturating_sub(base_in),
+            self.outgoing.total.saturating_sub(base_out),
+        )
+    }
+
+    /// 冻结 / 恢复历史记录 (图形停住，Cur/Avg/Min/Max/Ttl 继续更新)
+    pub fn set_history_frozen(&mut self, frozen: bool) {
+        self.history_frozen = frozen;
+    }
+
+    /// 用上次运行保存的速率历史 (front = 最新值) 预填图形，只在尚无历史时生效
+    ///
+    /// `gap` 为两次运行之间错过的采样数，以 0 填充，使旧数据停在真实的时间位置，
+    /// 而不是紧贴本次的第一个样本；空档超过历史长度时旧数据已移出图形，不再恢复。
+    pub fn restore_history(&mut self, incoming: &[f64], outgoing: &[f64], gap: usize) {
+        if !self.incoming_history.is_empty() || gap >= HISTORY_LEN {
+            return;
+        }
+        let len = incoming.len().min(outgoing.len()).min(HISTORY_LEN - gap);
+        let padded = |saved: &[f64]| {
+            std::iter::repeat_n(0.0, gap)
+                .chain(saved[..len].iter().copied())
+                .collect::<VecDeque<f64>>()
+        };
+        self.incoming_history = padded(incoming);
+        self.outgoing_history = padded(outgoing);
+        // 上次运行的样本没有保存时刻，按设定间隔向前推算 (本次第一个样本之前)
+        self.history_secs = (1..=self.incoming_history.len())
+            .map(|i| -(i as f64) * self.interval_secs)
+            .collect();
+        self.restored = self.incoming_history.len();
+    }
+
+    /// 收方向速率历史及各样本的时刻: `(秒, bytes/s)`，从最新到最旧
+    ///
+    /// 时刻相对本次运行的第一个样本 (与 [`Spike::at_secs`] 同一起点)，按实际采样时间记录，
+    /// 不假定固定间隔；上次运行恢复的样本为按间隔推算的负值。
+    pub fn incoming_timed(&self) -> impl DoubleEndedIterator<Item = (f64, f64)> + '_ {
+        self.history_secs.iter().copied().zip(self.incoming_history.iter().copied())
+    }
+
+    /// 发方向速率历史及各样本的时刻，见 [`Self::incoming_timed`]
+    pub fn outgoing_timed(&self) -> impl DoubleEndedIterator<Item = (f64, f64)> + '_ {
+        self.history_secs.iter().copied().zip(self.outgoing_history.iter().copied())
+    }
+
+    /// 速率历史中来自上次运行的样本数 (位于最旧的一端，图形中以灰色显示)
+    pub fn restored_len(&self) -> usize {
+        self.restored
+    }
+
+    /// 数据源暂时没有数据时，把当前速率归零 (Avg/Min/Max/Ttl 保持不变)
+    pub fn clear_current(&mut self) {
+        self.incoming.current = 0.0;
+        self.outgoing.current = 0.0;
+    }
+
+    /// 导出当前统计与速率历史的快照，不暴露引擎内部状态
+    pub fn snapshot(&self) -> EngineSnapshot {
+        EngineSnapshot {
+            incoming: self.incoming.clone(),
+            outgoing: self.outgoing.clone(),
+            incoming_history: self.incoming_history.iter().copied().collect(),
+            outgoing_history: self.outgoing_history.iter().copied().collect(),
+            history_secs: self.history_secs.iter().copied().collect(),
+        }
+    }
+
+    /// 收发速率持续为 0 的时长 (秒)，有流量时为 0
+    pub fn idle_secs(&self) -> f64 {
+        self.idle_since
+            .map_or(0.0, |since| (self.last_elapsed - since).max(0.0))
+    }
+}
+
+/// 计算一组速率的中位数 (偶数个时取中间两个的平均)
+pub fn median(values: impl Iterator<Item = f64>) -> f64 {
+    let mut v: Vec<f64> = values.collect();
+    if v.is_empty() {
+        return 0.0;
+    }
+    v.sort_by(f64::total_cmp);
+    let mid = v.len() / 2;
+    if v.len().is_multiple_of(2) {
+        (v[mid - 1] + v[mid]) / 2.0
+    } else {
+        v[mid]
+    }
 }
 
 // ─── 格式化工具函数 ───────────────────────────────────────
 
-use crate::Unit;
+/// 显示单位: 速率以 bit 还是 byte 计，以及前缀的进制与写法
+///
+/// - `Bit` / `Byte`: 与 nload 相同，1024 进制，写作 kBit/s、KB/s、kByte
+/// - `BitSi` / `ByteSi`: SI 十进制 (1 kB = 1000 B)，写作 kbit/s、kB/s
+/// - `BitIec` / `ByteIec`: IEC 二进制 (1 KiB = 1024 B)，写作 Kibit/s、KiB/s
+///
+/// 累计字节数 (Ttl 等) 总是以字节显示，只跟随前缀的进制与写法。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    /// 以 Bit/s 显示速率 (默认)
+    Bit,
+    /// 以 Byte/s 显示速率
+    Byte,
+    /// 以 bit/s 显示速率，SI 前缀 (k = 1000)
+    BitSi,
+    /// 以 Byte/s 显示速率，SI 前缀 (k = 1000)
+    ByteSi,
+    /// 以 bit/s 显示速率，IEC 前缀 (Ki = 1024)
+    BitIec,
+    /// 以 Byte/s 显示速率，IEC 前缀 (Ki = 1024)
+    ByteIec,
+}
+
+impl Unit {
+    /// 运行时切换 bit / byte，保持前缀写法 (u 键)
+    pub fn next(self) -> Self {
+        match self {
+            Unit::Bit => Unit::Byte,
+            Unit::Byte => Unit::Bit,
+            Unit::BitSi => Unit::ByteSi,
+            Unit::ByteSi => Unit::BitSi,
+            Unit::BitIec => Unit::ByteIec,
+            Unit::ByteIec => Unit::BitIec,
+        }
+    }
+
+    /// 运行时切换前缀写法 (nload → SI → IEC → nload)，保持 bit / byte (k 键)
+    pub fn next_prefix(self) -> Self {
+        match self {
+            Unit::Bit => Unit::BitSi,
+            Unit::BitSi => Unit::BitIec,
+            Unit::BitIec => Unit::Bit,
+            Unit::Byte => Unit::ByteSi,
+            Unit::ByteSi => Unit::ByteIec,
+            Unit::ByteIec => Unit::Byte,
+        }
+    }
+
+    /// 速率是否以 bit 显示
+    pub fn is_bits(self) -> bool {
+        matches!(self, Unit::Bit | Unit::BitSi | Unit::BitIec)
+    }
+
+    /// 前缀的进制: SI 为 1000，其余为 1024
+    pub fn base(self) -> f64 {
+        match self {
+            Unit::BitSi | Unit::ByteSi => 1000.0,
+            _ => 1024.0,
+        }
+    }
+
+    /// 状态栏等处的简短名称，如 "bit/s"、"byte/s SI"
+    pub fn label(self) -> &'static str {
+        match self {
+            Unit::Bit => "bit/s",
+            Unit::Byte => "byte/s",
+            Unit::BitSi => "bit/s SI",
+            Unit::ByteSi => "byte/s SI",
+            Unit::BitIec => "bit/s IEC",
+            Unit::ByteIec => "byte/s IEC",
+        }
+    }
 
-/// 根据单位选择格式化速率
+    /// 速率的单位后缀，依次为无前缀、k、M、G
+    fn speed_suffixes(self) -> [&'static str; 4] {
+        match self {
+            Unit::Bit => ["Bit/s", "kBit/s", "MBit/s", "GBit/s"],
+            Unit::Byte => ["B/s", "KB/s", "MB/s", "GB/s"],
+            Unit::BitSi => ["bit/s", "kbit/s", "Mbit/s", "Gbit/s"],
+            Unit::ByteSi => ["B/s", "kB/s", "MB/s", "GB/s"],
+            Unit::BitIec => ["bit/s", "Kibit/s", "Mibit/s", "Gibit/s"],
+            Unit::ByteIec => ["B/s", "KiB/s", "MiB/s", "GiB/s"],
+        }
+    }
+
+    /// 字节数的单位后缀，依次为无前缀、k、M、G
+    fn bytes_suffixes(self) -> [&'static str; 4] {
+        match self {
+            Unit::Bit | Unit::Byte => ["Byte", "kByte", "MByte", "GByte"],
+            Unit::BitSi | Unit::ByteSi => ["B", "kB", "MB", "GB"],
+            Unit::BitIec | Unit::ByteIec => ["B", "KiB", "MiB", "GiB"],
+        }
+    }
+}
+
+/// `scale` 对应的前缀指数 (0 = 无前缀 … 3 = G)
+fn prefix_exp(scale: f64, base: f64) -> i32 {
+    if scale >= base * base * base {
+        3
+    } else if scale >= base * base {
+        2
+    } else if scale >= base {
+        1
+    } else {
+        0
+    }
+}
+
+/// 根据单位选择格式化速率 (按数值自身大小选择 k / M / G 前缀)
 pub fn format_speed_unit(bytes_per_sec: f64, unit: Unit) -> String {
-    match unit {
-        Unit::Bit => {
-            let bits = bytes_per_sec * 8.0;
-            if bits >= 1024.0 * 1024.0 * 1024.0 {
-                format!("{:.2} GBit/s", bits / (1024.0 * 1024.0 * 1024.0))
-            } else if bits >= 1024.0 * 1024.0 {
-                format!("{:.2} MBit/s", bits / (1024.0 * 1024.0))
-            } else if bits >= 1024.0 {
-                format!("{:.2} kBit/s", bits / 1024.0)
-            } else {
-                format!("{:.2} Bit/s", bits)
+    format_speed_scaled(bytes_per_sec, unit, bytes_per_sec)
+}
+
+/// 按 `scale` 的大小选择前缀来格式化速率 (--fixed-unit)
+///
+/// 同一面板的数值都以面板刻度选前缀，单位只随刻度变化，不会每次刷新在 KB/s 与 MB/s 之间跳动。
+pub fn format_speed_scaled(bytes_per_sec: f64, unit: Unit, scale: f64) -> String {
+    let factor = if unit.is_bits() { 8.0 } else { 1.0 };
+    let base = unit.base();
+    let exp = prefix_exp(scale * factor, base);
+    format!("{:.2} {}", bytes_per_sec * factor / base.powi(exp), unit.speed_suffixes()[exp as usize])
+}
+
+/// 秒数格式化为 HH:MM:SS
+pub fn format_hms(secs: u64) -> String {
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// 将字节数转为人类可读格式，前缀的进制与写法跟随 `unit`
+pub fn format_bytes(total_bytes: u64, unit: Unit) -> String {
+    let b = total_bytes as f64;
+    let base = unit.base();
+    let exp = prefix_exp(b, base);
+    format!("{:.2} {}", b / base.powi(exp), unit.bytes_suffixes()[exp as usize])
+}
+
+/// 精确字节数，带千位分隔符 (如 "1,234,567 B")
+pub fn format_bytes_exact(total_bytes: u64) -> String {
+    format_bytes_grouped(total_bytes, Some(','))
+}
+
+/// 精确字节数，使用指定的千位分隔符；`None` 表示不分组 (如 "1234567 B")
+pub fn format_bytes_grouped(total_bytes: u64, sep: Option<char>) -> String {
+    let digits = total_bytes.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3 * 3 + 2);
+    for (i, ch) in digits.chars().enumerate() {
+        if let Some(sep) = sep {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(sep);
             }
         }
-        Unit::Byte => {
-            if bytes_per_sec >= 1024.0 * 1024.0 * 1024.0 {
-                format!("{:.2} GB/s", bytes_per_sec / (1024.0 * 1024.0 * 1024.0))
-            } else if bytes_per_sec >= 1024.0 * 1024.0 {
-                format!("{:.2} MB/s", bytes_per_sec / (1024.0 * 1024.0))
-            } else if bytes_per_sec >= 1024.0 {
-                format!("{:.2} KB/s", bytes_per_sec / 1024.0)
-            } else {
-                format!("{:.2} B/s", bytes_per_sec)
+        out.push(ch);
+    }
+    out.push_str(" B");
+    out
+}
+
+/// 根据 LC_ALL / LC_NUMERIC / LANG 推断千位分隔符
+///
+/// 只识别常见语言: 德语区等用 `.`，法语 / 俄语 / 北欧等用空格，瑞士用 `'`，
+/// 其余 (含 C / POSIX / 未设置) 用 `,`。
+pub fn locale_thousands_sep() -> char {
+    let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|k| std::env::var(k).ok())
+        .find(|v| !v.is_empty())
+        .unwrap_or_default();
+    // "de_DE.UTF-8" / "fr_FR@euro" → ("de", "DE") / ("fr", "FR")
+    let base = locale.split(['.', '@']).next().unwrap_or_default();
+    let (lang, region) = base.split_once('_').unwrap_or((base, ""));
+    match (lang, region) {
+        (_, "CH") | (_, "LI") => '\'',
+        ("de" | "es" | "it" | "pt" | "nl" | "da" | "id" | "tr" | "el", _) => '.',
+        ("fr" | "ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "hu" | "bg", _) => ' ',
+        _ => ',',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造一次采样；发方向计数取收方向的一半，便于区分两个方向
+    fn snap(elapsed_secs: f64, bytes_recv: u64) -> Snapshot {
+        Snapshot {
+            elapsed_secs,
+            bytes_recv,
+            bytes_sent: bytes_recv / 2,
+        }
+    }
+
+    /// 从 `start` 开始，按 `interval` 秒的间隔依次喂入每个采样间隔的收方向增量
+    fn feed(engine: &mut StatisticsEngine, interval: f64, start: u64, deltas: &[u64]) {
+        engine.update(snap(0.0, start));
+        let mut bytes = start;
+        for (i, d) in deltas.iter().enumerate() {
+            bytes += d;
+            engine.update(snap((i + 1) as f64 * interval, bytes));
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "expected {expected}, got {actual}");
+    }
+
+    /// 逐项比较速率历史 (front = 最新值)
+    fn assert_history(history: &VecDeque<f64>, expected: &[f64]) {
+        assert_eq!(history.len(), expected.len(), "history {history:?}");
+        for (&actual, &expected) in history.iter().zip(expected) {
+            assert_close(actual, expected);
+        }
+    }
+
+    #[test]
+    fn rate_uses_elapsed_time_not_nominal_interval() {
+        // 标称间隔 500ms，实际间隔为 0.3s 与 1.7s，两段的真实速率都是 2000 B/s
+        let mut engine = StatisticsEngine::new(500, 300);
+        engine.update(snap(0.0, 0));
+        engine.update(snap(0.3, 600));
+        assert_close(engine.incoming.current, 600.0 / 0.3);
+        engine.update(snap(2.0, 600 + 3400));
+        assert_close(engine.incoming.current, 3400.0 / 1.7);
+        assert_close(engine.outgoing.current, 1700.0 / 1.7);
+    }
+
+    #[test]
+    fn median_filter_rejects_single_tick_spike() {
+        let deltas = [1000, 1000, 1000, 50_000, 1000, 1000];
+        // 从突发那一刻起，到它移出 3 个样本的窗口为止，当前速率都不受影响
+        for ticks in 4..=deltas.len() {
+            let mut engine = StatisticsEngine::new(1000, 300);
+            engine.set_median_window(3);
+            feed(&mut engine, 1.0, 0, &deltas[..ticks]);
+            assert_close(engine.incoming.current, 1000.0);
+            // Max 仍使用未滤波的原始值
+            assert_close(engine.incoming.maximum, 50_000.0);
+        }
+    }
+
+    #[test]
+    fn sma_and_ema_averages() {
+        // 1s 间隔，每秒的速率依次为 100、300、500 B/s
+        let deltas = [100, 300, 500];
+        let cases = [
+            // SMA: 窗口内总字节数 / 时长
+            (AvgMethod::Sma, [100.0, 200.0, 300.0]),
+            // EMA (alpha = 0.5): 100 → 100 + 0.5 × (300 - 100) = 200 → 200 + 0.5 × (500 - 200) = 350
+            (AvgMethod::Ema { alpha: 0.5 }, [100.0, 200.0, 350.0]),
+        ];
+        for (method, expected) in cases {
+            for (ticks, average) in (1..=deltas.len()).zip(expected) {
+                let mut engine = StatisticsEngine::new(1000, 300);
+                engine.set_avg_method(method);
+                feed(&mut engine, 1.0, 0, &deltas[..ticks]);
+                assert_close(engine.incoming.average, average);
             }
         }
     }
-}
 
-/// 将字节数转为人类可读格式
-pub fn format_bytes(total_bytes: u64) -> String {
-    let b = total_bytes as f64;
-    if b >= 1024.0 * 1024.0 * 1024.0 {
-        format!("{:.2} GByte", b / (1024.0 * 1024.0 * 1024.0))
-    } else if b >= 1024.0 * 1024.0 {
-        format!("{:.2} MByte", b / (1024.0 * 1024.0))
-    } else if b >= 1024.0 {
-        format!("{:.2} kByte", b / 1024.0)
-    } else {
-        format!("{:.2} Byte", b)
+    #[test]
+    fn grouped_bytes() {
+        assert_eq!(format_bytes_grouped(999, Some(',')), "999 B");
+        assert_eq!(format_bytes_grouped(1000, Some(',')), "1,000 B");
+        assert_eq!(format_bytes_grouped(1_000_000, Some(',')), "1,000,000 B");
+        assert_eq!(format_bytes_grouped(1_000_000, Some('.')), "1.000.000 B");
+        assert_eq!(format_bytes_grouped(1_000_000, None), "1000000 B");
+    }
+
+    #[test]
+    fn window_total_drops_samples_older_than_window() {
+        // 每个增量在 2s 后移出窗口
+        let deltas = [100, 200, 300, 400];
+        for (ticks, expected) in (1..=deltas.len()).zip([100, 300, 500, 700]) {
+            let mut engine = StatisticsEngine::new(1000, 300);
+            engine.set_window_total(2);
+            feed(&mut engine, 1.0, 0, &deltas[..ticks]);
+            assert_eq!(engine.window_total(), Some((expected, expected / 2)));
+        }
+    }
+
+    #[test]
+    fn rate_inputs_report_raw_delta_and_elapsed_time() {
+        let mut engine = StatisticsEngine::new(500, 300);
+        engine.update(snap(0.3, 600));
+        assert!(engine.rate_inputs().is_none());
+        engine.update(snap(2.0, 600 + 3400));
+        let inputs = engine.rate_inputs().unwrap();
+        assert_eq!(inputs.delta_recv, 3400);
+        assert_eq!(inputs.delta_sent, 1700);
+        assert_close(inputs.dt, 1.7);
+    }
+
+    #[test]
+    fn steady_rate() {
+        let mut engine = StatisticsEngine::new(500, 300);
+        feed(&mut engine, 0.5, 0, &[500; 10]);
+        let st = &engine.incoming;
+        assert_close(st.current, 1000.0);
+        assert_close(st.average, 1000.0);
+        assert_close(st.minimum, 1000.0);
+        assert_close(st.maximum, 1000.0);
+        assert_eq!(st.total, 5000);
+        assert_history(&engine.incoming_history, &[1000.0; 10]);
+        assert_history(&engine.outgoing_history, &[500.0; 10]);
+    }
+
+    #[test]
+    fn ramp() {
+        let mut engine = StatisticsEngine::new(1000, 300);
+        feed(&mut engine, 1.0, 0, &[100, 200, 300, 400, 500]);
+        let st = &engine.incoming;
+        assert_close(st.current, 500.0);
+        assert_close(st.average, 300.0);
+        assert_close(st.minimum, 100.0);
+        assert_close(st.maximum, 500.0);
+        assert_eq!(st.total, 1500);
+        assert_history(&engine.incoming_history, &[500.0, 400.0, 300.0, 200.0, 100.0]);
+        assert_history(&engine.outgoing_history, &[250.0, 200.0, 150.0, 100.0, 50.0]);
+    }
+
+    #[test]
+    fn one_tick_spike() {
+        let mut engine = StatisticsEngine::new(1000, 300);
+        feed(&mut engine, 1.0, 0, &[1000, 1000, 10_000, 1000, 1000]);
+        let st = &engine.incoming;
+        assert_close(st.current, 1000.0);
+        assert_close(st.average, 14_000.0 / 5.0);
+        assert_close(st.minimum, 1000.0);
+        assert_close(st.maximum, 10_000.0);
+        assert_eq!(st.total, 14_000);
+        assert_history(&engine.incoming_history, &[1000.0, 1000.0, 10_000.0, 1000.0, 1000.0]);
+        assert_history(&engine.outgoing_history, &[500.0, 500.0, 5000.0, 500.0, 500.0]);
+    }
+
+    #[test]
+    fn counter_reset() {
+        let mut engine = StatisticsEngine::new(1000, 300);
+        feed(&mut engine, 1.0, 50_000, &[1000, 1000]);
+        // 网卡重新启用后计数器从 0 开始: 负增量按 0 计，不产生虚假的速率
+        engine.update(snap(3.0, 200));
+        assert_close(engine.incoming.current, 0.0);
+        engine.update(snap(4.0, 1200));
+        let st = &engine.incoming;
+        assert_close(st.current, 1000.0);
+        assert_close(st.minimum, 0.0);
+        assert_close(st.maximum, 1000.0);
+        assert_eq!(st.total, 1200);
+        assert_history(&engine.incoming_history, &[1000.0, 0.0, 1000.0, 1000.0]);
+        assert_history(&engine.outgoing_history, &[500.0, 0.0, 500.0, 500.0]);
+    }
+
+    #[test]
+    fn counter_wraparound() {
+        // 32 位计数器回绕
+        let start = u64::from(u32::MAX) - 1500;
+        let mut engine = StatisticsEngine::new(1000, 300);
+        feed(&mut engine, 1.0, start, &[1000]);
+        engine.update(snap(2.0, 500));
+        assert_close(engine.incoming.current, 0.0);
+        engine.update(snap(3.0, 1500));
+        let st = &engine.incoming;
+        assert_close(st.current, 1000.0);
+        assert_close(st.maximum, 1000.0);
+        assert_eq!(st.total, 1500);
+        assert_history(&engine.incoming_history, &[1000.0, 0.0, 1000.0]);
+        assert_history(&engine.outgoing_history, &[500.0, 0.0, 500.0]);
+
+        // 64 位计数器回绕同样不产生虚假的速率
+        let mut engine = StatisticsEngine::new(1000, 300);
+        feed(&mut engine, 1.0, u64::MAX - 1500, &[1000]);
+        engine.update(snap(2.0, 500));
+        assert_close(engine.incoming.current, 0.0);
+        assert_close(engine.incoming.maximum, 1000.0);
+        assert_history(&engine.incoming_history, &[0.0, 1000.0]);
+    }
+
+    #[test]
+    fn recent_max_expires_after_window() {
+        // t = 1s 的 5000 B/s 突发在 3s 窗口内保持为峰值，t = 4s 时移出窗口
+        let deltas = [5000, 1000, 1000, 1000, 1000];
+        for (ticks, peak) in (1..=deltas.len()).zip([5000.0, 5000.0, 5000.0, 1000.0, 1000.0]) {
+            let mut engine = StatisticsEngine::new(1000, 300);
+            engine.set_recent_max(3);
+            feed(&mut engine, 1.0, 0, &deltas[..ticks]);
+            let (recent_in, recent_out) = engine.recent_max().unwrap();
+            assert_close(recent_in, peak);
+            assert_close(recent_out, peak / 2.0);
+            // 只增不减的 Max 仍保留突发
+            assert_close(engine.incoming.maximum, 5000.0);
+        }
     }
 }
@@ -1,31 +1,61 @@
 //! 基于 ratatui 的 TUI 界面渲染
 //! 仿 nload 的双面板布局：上半 Incoming / 下半 Outgoing
 
+use std::borrow::Cow;
 use std::collections::VecDeque;
+use std::time::Duration;
 
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::Paragraph,
+    widgets::{Block, Borders, Cell, Clear, Gauge, Paragraph, Row, Sparkline, Table},
     Frame,
 };
 
-use crate::graph;
-use crate::stats::{self, TrafficStats};
-use crate::{App, BarStyle, Unit};
+use winload::graph::{self, Downsample};
+use winload::stats::{self, StatisticsEngine, TrafficStats, HISTORY_LEN};
+use winload::Unit;
+use winload::collector::DeviceInfo;
+use crate::process::{ProcessRate, ProcessSort};
+use crate::{AddrFamily, App, BarStyle, DeviceView, StatusField};
 #[cfg(target_os = "windows")]
 use crate::loopback::LoopbackMode;
 
+/// 完整双面板布局所需的最小终端尺寸
+const FULL_MIN_HEIGHT: u16 = 10;
+const FULL_MIN_WIDTH: u16 = 40;
+/// 紧凑视图 (网卡名 + 收发迷你图) 所需的最小尺寸，再小则提示终端太小
+const COMPACT_MIN_HEIGHT: u16 = 3;
+const COMPACT_MIN_WIDTH: u16 = 20;
+/// 总览中每格的最小宽度与高度 (名称 + 收 + 发 + 空行)
+const OVERVIEW_TILE_WIDTH: u16 = 40;
+const OVERVIEW_TILE_HEIGHT: u16 = 4;
+
+/// 完整布局中收发面板希望至少保留的行数，不足时按顺序收起头部的提示行与分隔线
+const PANELS_COMFORT_HEIGHT: u16 = 8;
+
 /// 主绘制入口
 pub fn draw(frame: &mut Frame, app: &App) {
     let area = frame.area();
 
-    if area.height < 10 || area.width < 40 {
+    if area.height < COMPACT_MIN_HEIGHT || area.width < COMPACT_MIN_WIDTH {
         draw_too_small(frame, area, app.emoji);
         return;
     }
 
+    // 没有可用网卡（无接口 / 权限不足）时显示提示，而不是空白界面
+    if app.current_view().is_none() {
+        draw_no_devices(frame, area, app.emoji);
+        return;
+    }
+
+    // 放不下完整布局 (小窗格 / tmux 分屏) 时退回紧凑视图
+    if area.height < FULL_MIN_HEIGHT || area.width < FULL_MIN_WIDTH {
+        draw_compact(frame, area, app);
+        return;
+    }
+
     // 判断当前是否为 Windows 平台的 Loopback 设备且未启用捕获
     let show_loopback_warning = {
         #[cfg(target_os = "windows")]
@@ -58,25 +88,289 @@ pub fn draw(frame: &mut Frame, app: &App) {
             .map(|v| v.info.name.to_lowercase().contains("loopback"))
             .unwrap_or(false);
 
-    let header_height = if show_loopback_warning || show_etw_warning || show_loopback_info { 3 } else { 2 };
+    let mut flags = HeaderFlags {
+        loopback_warning: show_loopback_warning,
+        etw_warning: show_etw_warning,
+        loopback_info: show_loopback_info,
+        // 实际采样间隔明显超过设定值时提示，速率可能被拉伸失真
+        lag_warning: app.sampling_behind(),
+        // --pcap 启动失败时提示原因，程序照常运行
+        proto_error: app.proto_error.is_some(),
+        // --log-alerts 无法写入系统日志时提示，告警仍会响铃
+        alert_log_error: app.alert_log_error.is_some(),
+        // 当前网卡静默超过 --idle-alert 阈值
+        idle_warning: app.current_view().is_some_and(|v| v.is_idle(app.idle_alert)),
+        // 当前网卡速率超过 --alert-rx / --alert-tx
+        threshold_warning: app.threshold_exceeded().is_some(),
+        // 数据源异常 (如 --remote 断线重连中)
+        source_status: app.source_status().is_some(),
+        separator: true,
+    };
+
+    let status_height = if app.status_fields.is_empty() { 0 } else { 1 };
+
+    // --top: 分隔线 + 表头 + N 行
+    // 进程页已占满内容区时不再重复显示 --top 面板
+    let top_height = if app.top_n > 0 && !app.process_tab { app.top_n as u16 + 2 } else { 0 };
+
+    // 终端较矮时逐行收起头部，把行数让给收发面板 (顺序见 HeaderFlags::collapse)
+    let header_budget = area
+        .height
+        .saturating_sub(top_height + status_height + 1 + PANELS_COMFORT_HEIGHT);
+    flags.collapse(header_budget);
+    let header_height = flags.height();
 
-    // 主布局: 头部(2或3行) + 内容 + 帮助栏(1行)
+    // 主布局: 头部(标题 + 提示行 + 分隔线，矮终端时收起) + 内容 + 进程表 + 状态栏(0或1行) + 帮助栏(1行)
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(header_height), // Header + (warning/info) + separator
             Constraint::Min(6),               // Content (Incoming + Outgoing)
+            Constraint::Length(top_height),    // Top processes (--top)
+            Constraint::Length(status_height), // Status bar (--status)
             Constraint::Length(1),             // Help bar
         ])
         .split(area);
 
-    draw_header(frame, chunks[0], app, show_loopback_warning, show_etw_warning, show_loopback_info);
+    draw_header(frame, chunks[0], app, &flags);
     draw_panels(frame, chunks[1], app);
-    draw_help(frame, chunks[2], app.emoji, app.bar_style);
+    if top_height > 0 {
+        draw_top(frame, chunks[2], app);
+    }
+    if status_height > 0 {
+        draw_status(frame, chunks[3], app);
+    }
+    // --confirm-quit 的确认提示优先于其他短提示
+    let notice = if app.confirming_quit { Some("Quit? (y/n)") } else { app.notice() };
+    draw_help(frame, chunks[4], app.emoji, app.bar_style, notice);
+    if app.countdown {
+        draw_countdown(frame, chunks[4], app);
+    }
+
+    if app.rate_debug {
+        draw_rate_debug(frame, chunks[1], app);
+    }
+    if app.spike_log {
+        draw_spike_log(frame, chunks[1], app);
+    }
+    if let Some(ref text) = app.debug_overlay {
+        draw_debug_overlay(frame, area, text, app.debug_scroll);
+    }
+}
+
+/// `i` 调试信息浮层: 居中的边框窗口，内容可滚动
+fn draw_debug_overlay(frame: &mut Frame, area: Rect, text: &str, scroll: u16) {
+    let width = (area.width * 9 / 10).max(20).min(area.width);
+    let height = (area.height * 8 / 10).max(5).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Debug info (\u{2191}/\u{2193} scroll, Esc/i close) ");
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(text.to_string()).block(block).scroll((scroll, 0)), popup);
+}
+
+/// 速率计算输入浮层 (--verbose / v 键): 在内容区右下角列出当前网卡的原始计数、增量与时长
+fn draw_rate_debug(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(view) = app.current_view() else {
+        return;
+    };
+    let label = Style::default().fg(Color::DarkGray);
+    let mut lines: Vec<Line> = Vec::new();
+    match view.engine.rate_inputs() {
+        None => lines.push(Line::styled("waiting for 2 samples\u{2026}", label)),
+        Some(r) => {
+            let signed = |d: i128| if d >= 0 { format!("+{d}") } else { d.to_string() };
+            let rate = |d: i128| if r.dt > 0.0 { d.max(0) as f64 / r.dt } else { 0.0 };
+            let row = |name: &str, prev: String, latest: String, delta: String| {
+                Line::from(vec![
+                    Span::styled(format!("{name:<6}"), label),
+                    Span::raw(format!("{prev:>16} {latest:>16} {delta:>12}")),
+                ])
+            };
+            lines.push(Line::styled(
+                format!("{:<6}{:>16} {:>16} {:>12}", "", "prev", "latest", "delta"),
+                label,
+            ));
+            lines.push(row(
+                "t (s)",
+                format!("{:.3}", r.prev.elapsed_secs),
+                format!("{:.3}", r.latest.elapsed_secs),
+                format!("{:.3}", r.dt),
+            ));
+            lines.push(row(
+                "in",
+                r.prev.bytes_recv.to_string(),
+                r.latest.bytes_recv.to_string(),
+                signed(r.delta_recv),
+            ));
+            lines.push(row(
+                "out",
+                r.prev.bytes_sent.to_string(),
+                r.latest.bytes_sent.to_string(),
+                signed(r.delta_sent),
+            ));
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<6}", "rate"), label),
+                Span::raw(format!(
+                    "in {:.1} B/s, out {:.1} B/s",
+                    rate(r.delta_recv),
+                    rate(r.delta_sent)
+                )),
+            ]));
+            if r.delta_recv < 0 || r.delta_sent < 0 {
+                lines.push(Line::styled(
+                    "counter went backwards (reset/wrap): rate clamped to 0",
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+        }
+    }
+
+    let inner_width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let width = (inner_width + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    if width < 10 || height < 3 {
+        return;
+    }
+    let popup = Rect {
+        x: area.x + area.width - width,
+        y: area.y + area.height - height,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(format!(" Rate inputs: {} (v) ", view.info.name));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+/// 峰值记录浮层 (s 键): 在内容区右上角列出当前网卡收发方向速率最高的几次突发
+fn draw_spike_log(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(view) = app.current_view() else {
+        return;
+    };
+    let label = Style::default().fg(Color::DarkGray);
+    let (in_spikes, out_spikes) = view.engine.spikes();
+    let cell = |spike: Option<&winload::Spike>| {
+        spike.map_or_else(String::new, |s| {
+            format!(
+                "{:>14} +{}",
+                stats::format_speed_unit(s.speed, app.unit),
+                stats::format_hms(s.at_secs as u64)
+            )
+        })
+    };
+    let mut lines = vec![Line::styled(
+        format!("{:<3}{:>24}  {:>24}", "#", "Incoming", "Outgoing"),
+        label,
+    )];
+    let rows = in_spikes.len().max(out_spikes.len());
+    for i in 0..rows {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<3}", i + 1), label),
+            Span::raw(format!("{:>24}  {:>24}", cell(in_spikes.get(i)), cell(out_spikes.get(i)))),
+        ]));
+    }
+    if rows == 0 {
+        lines.push(Line::styled("no traffic yet", label));
+    }
+
+    let inner_width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let width = (inner_width + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    if width < 10 || height < 3 {
+        return;
+    }
+    let popup = Rect {
+        x: area.x + area.width - width,
+        y: area.y,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(format!(" Spikes: {} (s) ", view.info.name));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
 }
 
 // ─── Header ────────────────────────────────────────────────
 
+/// 头部标题下方的提示行开关
+struct HeaderFlags {
+    loopback_warning: bool,
+    etw_warning: bool,
+    loopback_info: bool,
+    lag_warning: bool,
+    proto_error: bool,
+    alert_log_error: bool,
+    idle_warning: bool,
+    threshold_warning: bool,
+    source_status: bool,
+    /// 头部底部的 "=" 分隔线
+    separator: bool,
+}
+
+impl HeaderFlags {
+    /// 头部总行数: 标题 + 提示行 + 分隔线
+    fn height(&self) -> u16 {
+        1 + [
+            self.loopback_warning,
+            self.etw_warning,
+            self.loopback_info,
+            self.lag_warning,
+            self.proto_error,
+            self.alert_log_error,
+            self.idle_warning,
+            self.threshold_warning,
+            self.source_status,
+            self.separator,
+        ]
+        .iter()
+        .filter(|&&b| b)
+        .count() as u16
+    }
+
+    /// 把头部收起到最多 `max_height` 行 (标题行始终保留)
+    ///
+    /// 舍弃顺序固定: 先是 loopback 相关的说明与采样落后提示，再是分隔线，
+    /// 最后才是与数据可信度直接相关的错误和告警。
+    fn collapse(&mut self, max_height: u16) {
+        let mut excess = self.height().saturating_sub(max_height.max(1));
+        let order = [
+            &mut self.loopback_warning,
+            &mut self.etw_warning,
+            &mut self.loopback_info,
+            &mut self.lag_warning,
+            &mut self.separator,
+            &mut self.alert_log_error,
+            &mut self.proto_error,
+            &mut self.source_status,
+            &mut self.idle_warning,
+            &mut self.threshold_warning,
+        ];
+        for flag in order {
+            if excess == 0 {
+                break;
+            }
+            if *flag {
+                *flag = false;
+                excess -= 1;
+            }
+        }
+    }
+}
+
 /// 将文本用空格填充到指定宽度
 fn pad_to_width(text: &str, width: usize) -> String {
     let text_len = text.chars().count();
@@ -87,14 +381,44 @@ fn pad_to_width(text: &str, width: usize) -> String {
     }
 }
 
-fn draw_header(frame: &mut Frame, area: Rect, app: &App, show_loopback_warning: bool, show_etw_warning: bool, show_loopback_info: bool) {
-    if let Some(view) = app.current_view() {
-        let addr_str = if !view.info.addrs.is_empty() {
-            format!(" [{}]", view.info.addrs[0])
-        } else {
-            String::new()
+/// 标题中的地址段，如 " [192.168.1.2 | 2001:db8::1]"
+///
+/// 按 `family` 选择显示的地址族；超出 `budget` 时用省略号截断 IPv6，
+/// 仍放不下则只保留 IPv4，再不行就不显示。
+fn format_addrs(info: &DeviceInfo, family: AddrFamily, budget: usize) -> String {
+    let v4 = info.addrs.first().filter(|_| family != AddrFamily::V6);
+    let v6 = info.addrs6.first().filter(|_| family != AddrFamily::V4);
+    let wrap = |inner: String| format!(" [{inner}]");
+    let fits = |s: &String| s.chars().count() <= budget;
+
+    let full = match (v4, v6) {
+        (Some(a), Some(b)) => wrap(format!("{a} | {b}")),
+        (Some(a), None) | (None, Some(a)) => wrap(a.clone()),
+        (None, None) => return String::new(),
+    };
+    if fits(&full) {
+        return full;
+    }
+    if let Some(v6) = v6 {
+        // 截断 IPv6: 固定部分 + 省略号至少保留 4 个字符
+        let fixed = match v4 {
+            Some(a) => format!(" [{a} | ]").chars().count(),
+            None => 3,
         };
+        let keep = budget.saturating_sub(fixed + 1);
+        if keep >= 4 {
+            let short: String = v6.chars().take(keep).collect::<String>() + "\u{2026}";
+            return match v4 {
+                Some(a) => wrap(format!("{a} | {short}")),
+                None => wrap(short),
+            };
+        }
+    }
+    v4.map(|a| wrap(a.clone())).filter(fits).unwrap_or_default()
+}
 
+fn draw_header(frame: &mut Frame, area: Rect, app: &App, flags: &HeaderFlags) {
+    if let Some(view) = app.current_view() {
         let is_loopback = view.info.name.to_lowercase().contains("loopback");
 
         // 在 loopback 设备上追加捕获模式标记
@@ -113,28 +437,70 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, show_loopback_warning:
             ""
         };
 
-        let header_text = if app.emoji {
-            format!(
-                "🖧 Device {}{} ({}/{}){} 📡:",
-                view.info.name,
-                addr_str,
-                app.current_idx + 1,
-                app.views.len(),
-                mode_tag,
-            )
-        } else {
-            format!(
-                "Device {}{} ({}/{}){}:",
-                view.info.name,
-                addr_str,
-                app.current_idx + 1,
-                app.views.len(),
-                mode_tag,
-            )
+        // 连续多次采集不到该网卡的数据时标注
+        let no_data_tag = if view.no_data() { " [no data]" } else { "" };
+        // --swap-direction: 提示收发已对调，避免与系统工具的读数混淆
+        let swap_tag = if view.swapped { " [swapped]" } else { "" };
+        // 收藏的网卡在名称后加星标
+        let star = match (view.favorite, app.unicode || app.emoji) {
+            (false, _) => "",
+            (true, true) => " ★",
+            (true, false) => " *",
+        };
+        // VPN 隧道接口加锁形标记 (平台无法判断时不显示)
+        let vpn_tag = match (view.info.vpn == Some(true), app.unicode || app.emoji) {
+            (false, _) => "",
+            (true, true) => " 🔒",
+            (true, false) => " [vpn]",
+        };
+
+        let make_header = |addr_str: &str| {
+            if app.emoji {
+                format!(
+                    "🖧 Device {}{}{}{} ({}/{}){}{}{} 📡:",
+                    view.info.name,
+                    star,
+                    vpn_tag,
+                    addr_str,
+                    app.current_idx + 1,
+                    app.views.len(),
+                    mode_tag,
+                    swap_tag,
+                    no_data_tag,
+                )
+            } else {
+                format!(
+                    "Device {}{}{}{} ({}/{}){}{}{}:",
+                    view.info.name,
+                    star,
+                    vpn_tag,
+                    addr_str,
+                    app.current_idx + 1,
+                    app.views.len(),
+                    mode_tag,
+                    swap_tag,
+                    no_data_tag,
+                )
+            }
         };
 
         let width = area.width as usize;
 
+        // 链路状态: ● 已连接 / ○ 断开 (平台无法获取时不显示)
+        let link_glyph = view.info.link_up.map(|up| {
+            if up {
+                ("\u{25cf} ", Color::Green)
+            } else {
+                ("\u{25cb} ", Color::Red)
+            }
+        });
+        let head_width = width.saturating_sub(if link_glyph.is_some() { 2 } else { 0 });
+
+        // 地址占用标题行剩余的宽度，放不下时截断 IPv6
+        let base_len = make_header("").chars().count();
+        let addr_str = format_addrs(&view.info, app.addr_family, head_width.saturating_sub(base_len));
+        let header_text = make_header(&addr_str);
+
         let header_style = match app.bar_style {
             BarStyle::Fill => Style::default()
                 .bg(Color::White)
@@ -149,17 +515,49 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, show_loopback_warning:
                 .add_modifier(Modifier::BOLD),
         };
 
-        let header_display = if app.bar_style == BarStyle::Fill {
-            pad_to_width(&header_text, width)
+        // 右侧显示所有网卡的总吞吐，空间不足时省略
+        let total_text = if app.show_total {
+            let arrows = if app.emoji { ("⬇️", "⬆️") } else { ("\u{2193}", "\u{2191}") };
+            format!(
+                "\u{3a3} {}{} {}{} ",
+                arrows.0,
+                stats::format_speed_unit(app.total_in, app.unit),
+                arrows.1,
+                stats::format_speed_unit(app.total_out, app.unit),
+            )
         } else {
-            header_text
+            String::new()
         };
+        let header_len = header_text.chars().count();
+        let total_len = total_text.chars().count();
 
-        let header = Line::from(Span::styled(header_display, header_style));
+        let mut header = if total_len > 0 && header_len + 1 + total_len <= head_width {
+            let gap = " ".repeat(head_width - header_len - total_len);
+            let gap_span = if app.bar_style == BarStyle::Fill {
+                Span::styled(gap, header_style)
+            } else {
+                Span::raw(gap)
+            };
+            Line::from(vec![
+                Span::styled(header_text, header_style),
+                gap_span,
+                Span::styled(total_text, header_style),
+            ])
+        } else {
+            let header_display = if app.bar_style == BarStyle::Fill {
+                pad_to_width(&header_text, head_width)
+            } else {
+                header_text
+            };
+            Line::from(Span::styled(header_display, header_style))
+        };
+        if let Some((glyph, color)) = link_glyph {
+            header.spans.insert(0, Span::styled(glyph, header_style.fg(color)));
+        }
 
         let mut lines = vec![header];
         
-        if show_loopback_warning {
+        if flags.loopback_warning {
             let warn_text = " \u{26a0} Loopback: use --npcap (npcap.com) or --etw";
             let warn_style = match app.bar_style {
                 BarStyle::Fill => Style::default().bg(Color::Red).fg(Color::White),
@@ -174,7 +572,7 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, show_loopback_warning:
             lines.push(Line::from(Span::styled(warn_display, warn_style)));
         }
 
-        if show_etw_warning {
+        if flags.etw_warning {
             let etw_text = if app.emoji {
                 " ⚠️ ETW: 大多数 Windows loopback 计数器为 0，建议使用 --npcap (npcap.com)"
             } else {
@@ -193,7 +591,7 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, show_loopback_warning:
             lines.push(Line::from(Span::styled(etw_display, etw_style)));
         }
 
-        if show_loopback_info {
+        if flags.loopback_info {
             if let Some(ref info) = app.loopback_info {
                 let info_text = format!(" {info}");
                 let info_style = match app.bar_style {
@@ -210,6 +608,117 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, show_loopback_warning:
             }
         }
 
+        if flags.lag_warning {
+            let lag_text = format!(
+                " \u{26a0} Sampling behind: ~{}ms between samples (interval {}ms)",
+                app.sample_gap.as_millis(),
+                app.tick_rate.as_millis(),
+            );
+            let lag_style = match app.bar_style {
+                BarStyle::Fill => Style::default().bg(Color::DarkGray).fg(Color::Yellow),
+                BarStyle::Color => Style::default().bg(Color::DarkGray).fg(Color::Yellow),
+                BarStyle::Plain => Style::default().fg(Color::DarkGray),
+            };
+            let lag_display = if app.bar_style == BarStyle::Fill {
+                pad_to_width(&lag_text, width)
+            } else {
+                lag_text
+            };
+            lines.push(Line::from(Span::styled(lag_display, lag_style)));
+        }
+
+        if flags.proto_error {
+            if let Some(ref err) = app.proto_error {
+                // 错误信息可能有多行，头部只显示第一行
+                let first = err.lines().next().unwrap_or_default();
+                let err_text = format!(" \u{26a0} --pcap unavailable: {first}");
+                let err_style = match app.bar_style {
+                    BarStyle::Fill => Style::default().bg(Color::Red).fg(Color::White),
+                    BarStyle::Color => Style::default().bg(Color::Red).fg(Color::White),
+                    BarStyle::Plain => Style::default().fg(Color::Red),
+                };
+                let err_display = if app.bar_style == BarStyle::Fill {
+                    pad_to_width(&err_text, width)
+                } else {
+                    err_text
+                };
+                lines.push(Line::from(Span::styled(err_display, err_style)));
+            }
+        }
+
+        if flags.alert_log_error {
+            if let Some(ref err) = app.alert_log_error {
+                let err_text = format!(" \u{26a0} --log-alerts unavailable: {err}");
+                let err_style = match app.bar_style {
+                    BarStyle::Fill => Style::default().bg(Color::Yellow).fg(Color::Black),
+                    BarStyle::Color => Style::default().bg(Color::Yellow).fg(Color::Black),
+                    BarStyle::Plain => Style::default().fg(Color::Yellow),
+                };
+                let err_display = if app.bar_style == BarStyle::Fill {
+                    pad_to_width(&err_text, width)
+                } else {
+                    err_text
+                };
+                lines.push(Line::from(Span::styled(err_display, err_style)));
+            }
+        }
+
+        if flags.idle_warning {
+            let idle_text = format!(
+                " \u{26a0} No traffic on {} for {:.0}s",
+                view.info.name,
+                view.engine.idle_secs(),
+            );
+            // 按秒交替反色，形成闪烁效果
+            let flash = (view.engine.idle_secs() as u64).is_multiple_of(2);
+            let idle_style = match (app.bar_style, flash) {
+                (BarStyle::Plain, _) => Style::default().fg(Color::Red),
+                (_, true) => Style::default().bg(Color::Red).fg(Color::White),
+                (_, false) => Style::default().bg(Color::White).fg(Color::Red),
+            };
+            let idle_display = if app.bar_style == BarStyle::Fill {
+                pad_to_width(&idle_text, width)
+            } else {
+                idle_text
+            };
+            lines.push(Line::from(Span::styled(idle_display, idle_style)));
+        }
+
+        if let Some((dir, current, limit)) = app.threshold_exceeded().filter(|_| flags.threshold_warning) {
+            let flag = if dir == "in" { "--alert-rx" } else { "--alert-tx" };
+            let text = format!(
+                " \u{26a0} {} {dir} {} above {flag} {}",
+                view.info.name,
+                stats::format_speed_unit(current, app.unit),
+                stats::format_speed_unit(limit, app.unit),
+            );
+            let style = match app.bar_style {
+                BarStyle::Plain => Style::default().fg(Color::Red),
+                _ => Style::default().bg(Color::Red).fg(Color::White),
+            };
+            let display = if app.bar_style == BarStyle::Fill {
+                pad_to_width(&text, width)
+            } else {
+                text
+            };
+            lines.push(Line::from(Span::styled(display, style)));
+        }
+
+        if let Some(status) = app.source_status().filter(|_| flags.source_status) {
+            let status_text = format!(" \u{26a0} {status}");
+            let status_style = match app.bar_style {
+                BarStyle::Fill => Style::default().bg(Color::Yellow).fg(Color::Black),
+                BarStyle::Color => Style::default().bg(Color::Yellow).fg(Color::Black),
+                BarStyle::Plain => Style::default().fg(Color::Yellow),
+            };
+            let status_display = if app.bar_style == BarStyle::Fill {
+                pad_to_width(&status_text, width)
+            } else {
+                status_text
+            };
+            lines.push(Line::from(Span::styled(status_display, status_style)));
+        }
+
         let text_height = lines.len() as u16;
         frame.render_widget(
             Paragraph::new(lines),
@@ -219,81 +728,246 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, show_loopback_warning:
             },
         );
 
-        let sep_width = (area.width as usize).min(120);
-        let separator = Line::from(Span::styled(
-            "=".repeat(sep_width),
-            Style::default().fg(Color::Cyan),
-        ));
-        frame.render_widget(
-            Paragraph::new(vec![separator]),
-            Rect {
-                y: area.y + text_height,
-                height: 1,
-                ..area
-            },
-        );
+        if flags.separator {
+            let sep_width = (area.width as usize).min(120);
+            let separator = Line::from(Span::styled(
+                "=".repeat(sep_width),
+                Style::default().fg(Color::Cyan),
+            ));
+            frame.render_widget(
+                Paragraph::new(vec![separator]),
+                Rect {
+                    y: area.y + text_height,
+                    height: 1,
+                    ..area
+                },
+            );
+        }
     }
 }
 
 // ─── Panels ────────────────────────────────────────────────
 
+/// 单个面板的最小可用高度 (标签行 + 至少 2 行内容)
+const MIN_PANEL_HEIGHT: u16 = 3;
+
 fn draw_panels(frame: &mut Frame, area: Rect, app: &App) {
-    let panels = Layout::default()
+    if app.process_tab {
+        draw_processes(frame, area, app);
+        return;
+    }
+    if app.overview {
+        draw_overview(frame, area, app);
+        return;
+    }
+    if let Some((left, right)) = app.current_view().zip(app.compare_view()) {
+        draw_compare(frame, area, app, left, right);
+        return;
+    }
+    let panels = split_panels(area, app.split);
+    if let Some(view) = app.current_view() {
+        // --shared-scale: 两个面板使用同一个峰值，便于直接比较收发高度
+        let shared_peak = app.shared_scale.then(|| device_peak(app, view));
+        draw_traffic_panel(frame, panels[0], app, view, Flow::Incoming, shared_peak);
+        draw_traffic_panel(frame, panels[1], app, view, Flow::Outgoing, shared_peak);
+    }
+}
+
+/// 总览 (m 键): 每块网卡一格 (名称 + 收发两行速率与迷你图)，按列排满后换行
+///
+/// 迷你图共用所有网卡中的最大值作为刻度，空闲网卡因此是平的，一眼就能看出哪块网卡在走流量；
+/// 放不下时按页显示，保证选中的网卡可见。
+fn draw_overview(frame: &mut Frame, area: Rect, app: &App) {
+    let columns = (area.width / OVERVIEW_TILE_WIDTH).max(1) as usize;
+    let rows = (area.height / OVERVIEW_TILE_HEIGHT).max(1) as usize;
+    let per_page = columns * rows;
+    let first = app.current_idx / per_page * per_page;
+    let tile_width = area.width / columns as u16;
+    let scale = app
+        .views
+        .iter()
+        .map(|v| device_peak(app, v))
+        .fold(0.0_f64, f64::max)
+        .max(1.0) as u64;
+
+    for (i, view) in app.views.iter().enumerate().skip(first).take(per_page) {
+        let slot = i - first;
+        let tile = Rect {
+            x: area.x + (slot % columns) as u16 * tile_width,
+            y: area.y + (slot / columns) as u16 * OVERVIEW_TILE_HEIGHT,
+            width: tile_width.saturating_sub(1),
+            height: OVERVIEW_TILE_HEIGHT - 1,
+        };
+        let selected = i == app.current_idx;
+        let marker = match (selected, app.unicode) {
+            (true, true) => "\u{25b6} ",
+            (true, false) => "> ",
+            (false, _) => "  ",
+        };
+        let mut style = Style::default().add_modifier(Modifier::BOLD);
+        if selected {
+            style = style.add_modifier(Modifier::REVERSED);
+        } else if view.no_data() {
+            style = Style::default().fg(Color::DarkGray);
+        }
+        let title = format!("{marker}{} ({}/{})", view.info.name, i + 1, app.views.len());
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(title, style))),
+            Rect { height: 1, ..tile },
+        );
+        for (flow, offset) in [(Flow::Incoming, 1), (Flow::Outgoing, 2)] {
+            let row = Rect { y: tile.y + offset, height: 1, ..tile };
+            draw_rate_sparkline(frame, row, app, view, flow, Some(scale));
+        }
+    }
+
+    // 多页时在右下角标出页码
+    let pages = app.views.len().div_ceil(per_page);
+    if pages > 1 {
+        let text = format!(" page {}/{} ", first / per_page + 1, pages);
+        let width = text.len() as u16;
+        if area.width >= width && area.height > 0 {
+            let mark = Rect {
+                x: area.right() - width,
+                y: area.bottom() - 1,
+                width,
+                height: 1,
+            };
+            frame.render_widget(Paragraph::new(Span::styled(text, Style::default().fg(Color::DarkGray))), mark);
+        }
+    }
+}
+
+/// 按 --split 比例分配上下面板，保证两边都不低于最小高度
+fn split_panels(area: Rect, split: u16) -> std::rc::Rc<[Rect]> {
+    let in_height = (area.height as u32 * split as u32 / 100) as u16;
+    let in_height = in_height.clamp(
+        MIN_PANEL_HEIGHT.min(area.height),
+        area.height.saturating_sub(MIN_PANEL_HEIGHT).max(MIN_PANEL_HEIGHT.min(area.height)),
+    );
+    Layout::default()
         .direction(Direction::Vertical)
+        .constraints([Constraint::Length(in_height), Constraint::Min(0)])
+        .split(area)
+}
+
+/// 网卡收发两个方向绘制序列中的最大值
+fn device_peak(app: &App, view: &DeviceView) -> f64 {
+    plotted_history(app, &view.engine.incoming_history)
+        .iter()
+        .chain(plotted_history(app, &view.engine.outgoing_history).iter())
+        .cloned()
+        .fold(0.0_f64, f64::max)
+}
+
+/// --compare: 左右两栏各画一块网卡的收发面板，四个面板共用同一缩放上限
+fn draw_compare(frame: &mut Frame, area: Rect, app: &App, left: &DeviceView, right: &DeviceView) {
+    let shared_peak = device_peak(app, left).max(device_peak(app, right));
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(area);
-
-    if let Some(view) = app.current_view() {
-        let (in_label, out_label) = if app.emoji {
-            ("⬇️📥 Incoming", "⬆️📤 Outgoing")
+    for (column, view) in columns.iter().zip([left, right]) {
+        // 栏标题行 (网卡名) + 收发面板
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(*column);
+        let title = if app.emoji {
+            format!("🖧 {}", view.info.name)
         } else {
-            ("Incoming", "Outgoing")
+            view.info.name.clone()
         };
-        draw_traffic_panel(
-            frame,
-            panels[0],
-            in_label,
-            &view.engine.incoming,
-            &view.engine.incoming_history,
-            app.emoji,
-            app.unicode,
-            app.unit,
-            app.bar_style,
-            app.in_color,
-            app.fixed_max,
-            app.no_graph,
-        );
-        draw_traffic_panel(
-            frame,
-            panels[1],
-            out_label,
-            &view.engine.outgoing,
-            &view.engine.outgoing_history,
-            app.emoji,
-            app.unicode,
-            app.unit,
-            app.bar_style,
-            app.out_color,
-            app.fixed_max,
-            app.no_graph,
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(title, Style::default().add_modifier(Modifier::BOLD)))),
+            rows[0],
         );
+        let panels = split_panels(rows[1], app.split);
+        draw_traffic_panel(frame, panels[0], app, view, Flow::Incoming, Some(shared_peak));
+        draw_traffic_panel(frame, panels[1], app, view, Flow::Outgoing, Some(shared_peak));
+    }
+}
+
+/// 图形实际绘制的速率序列: --display-smooth 时为平滑后的副本
+///
+/// 缩放上限也按它计算，避免 TCP 锯齿的尖峰让刻度来回跳动；统计数值与 --live-value 仍用原始值。
+fn plotted_history<'a>(app: &App, history: &'a VecDeque<f64>) -> Cow<'a, VecDeque<f64>> {
+    if app.display_smooth == 0 {
+        Cow::Borrowed(history)
+    } else {
+        Cow::Owned(graph::smooth_history(history, app.display_smooth as usize))
+    }
+}
+
+/// 面板对应的流量方向
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    Incoming,
+    Outgoing,
+}
+
+impl Flow {
+    /// 面板标签，带方向箭头 (无 Unicode 时退回 ASCII 的 v / ^)
+    fn label(self, app: &App) -> &'static str {
+        match (self, app.emoji, app.unicode) {
+            (Flow::Incoming, true, _) => "⬇️📥 Incoming",
+            (Flow::Outgoing, true, _) => "⬆️📤 Outgoing",
+            (Flow::Incoming, false, true) => "\u{2193} Incoming",
+            (Flow::Outgoing, false, true) => "\u{2191} Outgoing",
+            (Flow::Incoming, false, false) => "v Incoming",
+            (Flow::Outgoing, false, false) => "^ Outgoing",
+        }
+    }
+
+    fn color(self, app: &App) -> Color {
+        match self {
+            Flow::Incoming => app.in_color,
+            Flow::Outgoing => app.out_color,
+        }
+    }
+
+    fn stats(self, engine: &StatisticsEngine) -> &TrafficStats {
+        match self {
+            Flow::Incoming => &engine.incoming,
+            Flow::Outgoing => &engine.outgoing,
+        }
+    }
+
+    fn history(self, engine: &StatisticsEngine) -> &VecDeque<f64> {
+        match self {
+            Flow::Incoming => &engine.incoming_history,
+            Flow::Outgoing => &engine.outgoing_history,
+        }
+    }
+
+    fn total_history(self, engine: &StatisticsEngine) -> &VecDeque<u64> {
+        match self {
+            Flow::Incoming => &engine.incoming_total_history,
+            Flow::Outgoing => &engine.outgoing_total_history,
+        }
     }
 }
 
 fn draw_traffic_panel(
     frame: &mut Frame,
     area: Rect,
-    label: &str,
-    stats: &TrafficStats,
-    history: &VecDeque<f64>,
-    emoji: bool,
-    unicode: bool,
-    unit: Unit,
-    bar_style: BarStyle,
-    graph_color: Color,
-    fixed_max: Option<f64>,
-    no_graph: bool,
+    app: &App,
+    view: &DeviceView,
+    flow: Flow,
+    shared_peak: Option<f64>,
 ) {
+    let (emoji, unit, bar_style) = (app.emoji, app.unit, app.bar_style);
+    let label = flow.label(app);
+    let floored;
+    let stats = if app.noise_floor_stats {
+        floored = subtract_noise_floor(flow.stats(&view.engine), app.noise_floor);
+        &floored
+    } else {
+        flow.stats(&view.engine)
+    };
+    let history = flow.history(&view.engine);
+    let plotted = plotted_history(app, history);
+    let graph_color = flow.color(app);
     if area.height < 2 || area.width < 20 {
         return;
     }
@@ -305,14 +979,44 @@ fn draw_traffic_panel(
         .split(area);
 
     // ── 标签行 ──
-    let peak = history.iter().cloned().fold(0.0_f64, f64::max);
-    let scale_max = if let Some(m) = fixed_max {
+    let peak = shared_peak.unwrap_or_else(|| plotted.iter().cloned().fold(0.0_f64, f64::max))
+        - app.graph_floor();
+    // --crop-max: 刻度固定为裁剪范围的高度，超出部分顶格
+    let scale_max = if let Some(top) = app.crop_max {
+        top - app.graph_floor()
+    } else if let Some(m) = app.fixed_max {
         m
     } else {
         graph::next_power_of_2_scaled(peak)
     };
-    let scale_label = graph::get_graph_scale_label_unit(scale_max, unit);
-    let label_text = format!("{label} ({scale_label}):");
+    // 裁剪时标签显示图形覆盖的速率区间
+    let scale_label = if app.crop_min.is_some() || app.crop_max.is_some() {
+        format!(
+            "{}\u{2013}{}",
+            stats::format_speed_unit(app.graph_floor(), unit),
+            stats::format_speed_unit(app.graph_floor() + scale_max, unit)
+        )
+    } else {
+        graph::get_graph_scale_label_unit(scale_max, unit)
+    };
+    // --fixed-unit: 面板内的速率都按刻度选前缀
+    let unit_scale = app.fixed_unit.then_some(scale_max);
+    let frozen_tag = if app.graph_frozen { " [graph frozen]" } else { "" };
+    // +/- 缩放时标出图形覆盖的时间跨度
+    let zoom_tag = match app.zoom {
+        0 => String::new(),
+        _ => {
+            let graph_width = area.width.saturating_sub(stat_column_width(app)).min(MAX_GRAPH_WIDTH) as usize;
+            let samples = graph_window(app, graph_width, history.len()).unwrap_or(graph_width);
+            format!(" [last {}]", format_span(view.interval * samples as u32))
+        }
+    };
+    let mut label_text = format!("{label} ({scale_label}){frozen_tag}{zoom_tag}:");
+    // 当前速率直接跟在标签后，不必扫视右侧统计栏
+    if app.label_rate {
+        label_text.push(' ');
+        label_text.push_str(&format_panel_speed(stats.current, unit, unit_scale));
+    }
     let width = area.width as usize;
 
     let label_style = match bar_style {
@@ -336,62 +1040,554 @@ fn draw_traffic_panel(
     let label_line = Line::from(Span::styled(label_display, label_style));
     frame.render_widget(Paragraph::new(vec![label_line]), panel_chunks[0]);
 
-    if no_graph {
+    let mut stat_rows = format_stats_rows(stats, unit, unit_scale, app.exact_total, app.thousands_sep);
+    stat_rows.extend(proto_rows(app, view, flow, unit_scale));
+    stat_rows.extend(recent_max_row(app, view, flow, unit_scale));
+    stat_rows.extend(window_total_row(app, view, flow));
+    if flow == Flow::Incoming {
+        stat_rows.extend(eta_row(app, view));
+    }
+
+    if app.no_graph {
         // ── 无图模式: 统计信息占满宽度 ──
-        draw_stats(frame, panel_chunks[1], stats, emoji, unit);
+        draw_stats(frame, panel_chunks[1], stat_rows, emoji);
     } else {
         // ── 内容区: 左侧图形 + 右侧统计 ──
-        let stat_width: u16 = if emoji { 28 } else { 24 };
         let content_chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Min(10), Constraint::Length(stat_width)])
+            .constraints([Constraint::Min(10), Constraint::Length(stat_column_width(app))])
             .split(panel_chunks[1]);
 
-        draw_graph(frame, content_chunks[0], history, scale_max, unicode, graph_color);
-        draw_stats(frame, content_chunks[1], stats, emoji, unit);
+        if app.gauge {
+            draw_gauge(frame, content_chunks[0], app, stats.current, scale_max, graph_color);
+        } else if app.cumulative && content_chunks[0].height >= 6 {
+            // --cumulative: 速率图下方追加累计字节迷你图 (约 1/3 高度)
+            let cum_height = (content_chunks[0].height / 3).max(3);
+            let graph_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(cum_height)])
+                .split(content_chunks[0]);
+            draw_graph(frame, graph_chunks[0], app, history, &view.engine, scale_max, graph_color);
+            draw_cumulative(frame, graph_chunks[1], app, flow.total_history(&view.engine), graph_color);
+        } else {
+            draw_graph(frame, content_chunks[0], app, history, &view.engine, scale_max, graph_color);
+        }
+        if let Some(reason) = stale_label(app, view) {
+            draw_stale(frame, content_chunks[0], reason);
+        }
+        draw_stats(frame, content_chunks[1], stat_rows, emoji);
+    }
+}
+
+/// --noise-floor-stats: 从显示的速率中减去噪声基线 (Ttl 保持真实值)
+fn subtract_noise_floor(stats: &TrafficStats, floor: f64) -> TrafficStats {
+    let sub = |v: f64| (v - floor).max(0.0);
+    TrafficStats {
+        current: sub(stats.current),
+        average: sub(stats.average),
+        minimum: sub(stats.minimum),
+        maximum: sub(stats.maximum),
+        total: stats.total,
     }
 }
 
 // ─── Graph ─────────────────────────────────────────────────
 
-fn draw_graph(frame: &mut Frame, area: Rect, history: &VecDeque<f64>, max_value: f64, unicode: bool, graph_color: Color) {
+/// 面板显示的不是实时数据时的原因 (见 [`App::data_fresh`])，按优先级: 冻结 > 数据源停止 > 该网卡无数据
+fn stale_label(app: &App, view: &DeviceView) -> Option<&'static str> {
+    if app.graph_frozen {
+        Some("FROZEN")
+    } else if !app.data_fresh {
+        Some("STALE")
+    } else if view.no_data() {
+        Some("NO DATA")
+    } else {
+        None
+    }
+}
+
+/// 非实时数据: 整个图形区域变暗，中央叠加暗灰色水印
+fn draw_stale(frame: &mut Frame, area: Rect, reason: &str) {
+    let buf = frame.buffer_mut();
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            if let Some(cell) = buf.cell_mut((x, y)) {
+                cell.modifier.insert(Modifier::DIM);
+            }
+        }
+    }
+    let text = format!(" {reason} ");
+    let width = text.len() as u16;
+    if area.width < width || area.height == 0 {
+        return;
+    }
+    let mark = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + area.height / 2,
+        width,
+        height: 1,
+    };
+    let style = Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD);
+    frame.render_widget(Paragraph::new(Span::styled(text, style)), mark);
+}
+
+/// --gauge: 当前速率占 --max 的百分比，以横向进度条显示 (最多 3 行高，垂直居中)
+fn draw_gauge(frame: &mut Frame, area: Rect, app: &App, current: f64, max_value: f64, graph_color: Color) {
+    let ratio = if max_value > 0.0 {
+        (current / max_value).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let height = area.height.min(3);
+    let gauge_area = Rect {
+        y: area.y + (area.height - height) / 2,
+        height,
+        ..area
+    };
+    let label = format!(
+        "{:.1}% of {}",
+        current / max_value.max(f64::MIN_POSITIVE) * 100.0,
+        stats::format_speed_unit(max_value, app.unit),
+    );
+    let gauge_style = match app.bar_style {
+        BarStyle::Fill | BarStyle::Color => Style::default().fg(graph_color).bg(Color::DarkGray),
+        BarStyle::Plain => Style::default().fg(graph_color),
+    };
+    let gauge = Gauge::default()
+        .gauge_style(gauge_style)
+        .ratio(ratio)
+        .label(Span::styled(label, Style::default().add_modifier(Modifier::BOLD)))
+        .use_unicode(app.unicode);
+    frame.render_widget(gauge, gauge_area);
+}
+
+/// 图形最大宽度: 历史最多保留 1024 个点，更宽的终端左侧留空
+const MAX_GRAPH_WIDTH: u16 = 1024;
+
+/// 面板右侧统计列的宽度 (精确字节数较长，统计列相应加宽)
+fn stat_column_width(app: &App) -> u16 {
+    (if app.emoji { 28 } else { 24 }) + if app.exact_total { 6 } else { 0 }
+}
+
+/// 放大时每屏至少显示的样本数
+const ZOOM_MIN_SAMPLES: usize = 4;
+
+/// 图形铺满 `width` 列所用的最近样本数 (--downsample / +/- 缩放)，None 表示每列一个样本
+fn graph_window(app: &App, width: usize, len: usize) -> Option<usize> {
+    match app.zoom {
+        // 未缩放: --downsample 把全部历史压缩进宽度
+        0 => app.downsample.filter(|_| len > width).map(|_| len),
+        z if z > 0 => Some((width >> z).max(ZOOM_MIN_SAMPLES)),
+        z => Some((width << -z).min(HISTORY_LEN)),
+    }
+}
+
+/// 时间跨度的简短写法，如 "45s"、"12m"、"2h5m"
+fn format_span(span: Duration) -> String {
+    let secs = span.as_secs();
+    if secs < 120 {
+        format!("{:.0}s", span.as_secs_f64())
+    } else if secs < 7200 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h{}m", secs / 3600, secs / 60 % 60)
+    }
+}
+
+/// `restored`: 历史最旧一端来自上次运行的样本数，这些列以灰色显示，与本次数据区分
+fn draw_graph(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    history: &VecDeque<f64>,
+    engine: &StatisticsEngine,
+    max_value: f64,
+    graph_color: Color,
+) {
+    // 超宽终端只在右侧绘制 MAX_GRAPH_WIDTH 列，避免无意义的整行计算
+    let area = if area.width > MAX_GRAPH_WIDTH {
+        Rect {
+            x: area.x + area.width - MAX_GRAPH_WIDTH,
+            width: MAX_GRAPH_WIDTH,
+            ..area
+        }
+    } else {
+        area
+    };
     let width = area.width as usize;
     let height = area.height as usize;
 
-    let lines = graph::render_graph(history, width, height, max_value, unicode);
+    let chars = &app.graph_chars;
+    let plotted = plotted_history(app, history);
+    let window = graph_window(app, width, history.len());
+    let plotted = match window {
+        Some(samples) => {
+            let mode = app.downsample.unwrap_or(Downsample::Max);
+            Cow::Owned(graph::window_history(&plotted, samples, width, mode))
+        }
+        None => plotted,
+    };
+    let lines = graph::render_graph(&plotted, width, height, max_value, chars, app.graph_floor());
+    // 第 restored_from 个样本起 (含) 为上次运行的历史
+    let restored_from = history.len() - engine.restored_len().min(history.len());
+    let sample_of_col = |col: usize| match window {
+        Some(samples) => graph::window_first_sample(samples, width, col),
+        None => col,
+    };
 
-    // 较暗的颜色用于低密度区域
-    let dim_color = Color::DarkGray;
+    // 直接写入缓冲区的单元格，不为每个字符分配 Span / String (热路径)
+    let buf = frame.buffer_mut();
+    for (row, line) in lines.iter().enumerate() {
+        // --heat: 按行所在高度占比着色 (底部绿 → 中间黄 → 顶部红)
+        let graph_color = if app.heat {
+            heat_color((height - row) as f64 / height as f64)
+        } else {
+            graph_color
+        };
+        // 低密度区域用同方向颜色的暗色版本，收发两个方向在部分填充格上也能区分
+        let dim_color = dim_variant(graph_color);
+        let y = area.y + row as u16;
+        for (col, ch) in line.chars().enumerate() {
+            let Some(cell) = buf.cell_mut((area.x + col as u16, y)) else {
+                continue;
+            };
+            // 空白格: --bg-char 指定时画暗灰色背景字符 (空格则保持无样式)
+            if ch == chars.empty {
+                let bg = app.bg_char.unwrap_or(ch);
+                cell.set_char(bg);
+                if bg != ' ' {
+                    cell.set_style(Style::default().fg(Color::DarkGray));
+                }
+                continue;
+            }
+            cell.set_char(ch);
+            // 上次运行的历史: 与本次之间可能有空档，统一灰色，不冒充连续数据
+            if sample_of_col(width - 1 - col) >= restored_from {
+                cell.set_style(Style::default().fg(Color::DarkGray));
+                continue;
+            }
+            // 颜色由同一字符集决定: full/high 用主色，low/dot 用暗色
+            let style = if chars.is_strong(ch) {
+                Style::default().fg(graph_color)
+            } else {
+                Style::default().fg(dim_color)
+            };
+            // --fade: 按列的新旧程度逐渐变暗 (最右列最新)
+            let style = if app.fade {
+                fade_style(style, width - 1 - col, width)
+            } else {
+                style
+            };
+            cell.set_style(style);
+        }
+    }
 
-    let styled_lines: Vec<Line> = lines
-        .iter()
-        .map(|line| {
-            let spans: Vec<Span> = line
-                .chars()
-                .map(|ch| match ch {
-                    // Unicode block chars
-                    '█' => Span::styled("█", Style::default().fg(graph_color)),
-                    '▓' => Span::styled("▓", Style::default().fg(graph_color)),
-                    '░' => Span::styled("░", Style::default().fg(dim_color)),
-                    '·' => Span::styled("·", Style::default().fg(dim_color)),
-                    // ASCII chars
-                    '#' => Span::styled("#", Style::default().fg(graph_color)),
-                    '|' => Span::styled("|", Style::default().fg(graph_color)),
-                    '.' => Span::styled(".", Style::default().fg(dim_color)),
-                    _ => Span::raw(" "),
-                })
-                .collect();
-            Line::from(spans)
-        })
+    // --time-grid: 相邻两列的样本时刻跨过 SECONDS 的整数倍时，在较新的一列画竖线，网格随数据一起滚动
+    if let Some(secs) = app.time_grid {
+        let secs = secs as f64;
+        let times: Vec<f64> = engine.incoming_timed().map(|(t, _)| t).collect();
+        let grid_cols: Vec<usize> = (0..width)
+            .filter(|&col| {
+                let age = width - 1 - col;
+                match (times.get(sample_of_col(age)), times.get(sample_of_col(age + 1))) {
+                    (Some(newer), Some(older)) => (newer / secs).floor() != (older / secs).floor(),
+                    _ => false,
+                }
+            })
+            .collect();
+        draw_time_grid(frame, area, app, &lines, &grid_cols);
+    }
+    if app.show_ceiling {
+        draw_ceiling(frame, area, app, &lines, max_value);
+    }
+    if app.live_value {
+        draw_live_value(frame, area, app, &lines, history, graph_color);
+    }
+    if app.legend {
+        draw_legend(frame, area, app, graph_color);
+    }
+}
+
+/// 图例 (--legend / l 键): 在图形右上角说明当前使用的字符与颜色，随开启的选项变化
+fn draw_legend(frame: &mut Frame, area: Rect, app: &App, graph_color: Color) {
+    let chars = &app.graph_chars;
+    let text_style = Style::default().fg(Color::DarkGray);
+    let mut spans: Vec<Span> = Vec::new();
+    let item = |spans: &mut Vec<Span>, symbol: Vec<Span<'static>>, desc: &str| {
+        if !spans.is_empty() {
+            spans.push(Span::styled("  ", text_style));
+        }
+        spans.extend(symbol);
+        spans.push(Span::styled(format!(" {desc}"), text_style));
+    };
+
+    // 数据字符: 满格 / 大半 / 部分 / 少量 (同一字符只列一次)
+    let dim = dim_variant(graph_color);
+    if app.heat {
+        let ramp = [0.1, 0.5, 0.9]
+            .iter()
+            .map(|&f| Span::styled(chars.full.to_string(), Style::default().fg(heat_color(f))))
+            .collect();
+        item(&mut spans, ramp, "low\u{2192}high");
+    } else {
+        item(&mut spans, vec![Span::styled(chars.full.to_string(), Style::default().fg(graph_color))], "full");
+    }
+    if chars.eighths {
+        let steps = ['▁', '▄', '▆'].iter().map(|c| c.to_string()).collect::<String>();
+        let color = if app.heat { heat_color(0.5) } else { graph_color };
+        item(&mut spans, vec![Span::styled(steps, Style::default().fg(color))], "1/8 steps");
+    } else if chars.high != chars.full {
+        let color = if app.heat { heat_color(0.5) } else { graph_color };
+        item(&mut spans, vec![Span::styled(chars.high.to_string(), Style::default().fg(color))], "high");
+    }
+    if !chars.eighths && chars.low != chars.high && chars.low != chars.full {
+        item(&mut spans, vec![Span::styled(chars.low.to_string(), Style::default().fg(dim))], "partial");
+    }
+    if !chars.eighths && ![chars.full, chars.high, chars.low].contains(&chars.dot) {
+        item(&mut spans, vec![Span::styled(chars.dot.to_string(), Style::default().fg(dim))], "trace");
+    }
+    if app.show_ceiling {
+        let dash = if app.unicode { '\u{2504}' } else { '-' };
+        item(&mut spans, vec![Span::styled(dash.to_string(), text_style)], "ceiling");
+    }
+    if app.fade {
+        item(&mut spans, vec![Span::styled("\u{2190}", text_style)], "older=dimmer");
+    }
+
+    let line = Line::from(spans);
+    let width = line.width() as u16;
+    if width > area.width || area.height == 0 {
+        return;
+    }
+    frame.render_widget(
+        Paragraph::new(line),
+        Rect {
+            x: area.x + area.width - width,
+            y: area.y,
+            width,
+            height: 1,
+        },
+    );
+}
+
+/// --show-ceiling: 在图形顶行的空白格上画出刻度上限标记线，左端内嵌上限数值
+///
+/// 只覆盖空白格，不占用额外的数据行；顶行有数据的列保持原样。
+fn draw_ceiling(frame: &mut Frame, area: Rect, app: &App, lines: &[String], max_value: f64) {
+    let Some(top) = lines.first() else {
+        return;
+    };
+    let dash = if app.unicode { '\u{2504}' } else { '-' };
+    let label = format!("{dash}{} ", stats::format_speed_unit(max_value, app.unit));
+    let style = Style::default().fg(Color::DarkGray);
+    let buf = frame.buffer_mut();
+    for (col, ch) in top.chars().enumerate() {
+        if ch != app.graph_chars.empty {
+            continue;
+        }
+        let mark = label.chars().nth(col).unwrap_or(dash);
+        if let Some(cell) = buf.cell_mut((area.x + col as u16, area.y)) {
+            cell.set_char(mark).set_style(style);
+        }
+    }
+}
+
+/// --time-grid: 在 `cols` 列的空白格上画暗灰色竖线，不覆盖图形本身
+fn draw_time_grid(frame: &mut Frame, area: Rect, app: &App, lines: &[String], cols: &[usize]) {
+    let mark = if app.unicode { '\u{2502}' } else { ':' };
+    let style = Style::default().fg(Color::DarkGray);
+    let buf = frame.buffer_mut();
+    for (row, line) in lines.iter().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        for &col in cols {
+            if chars.get(col) != Some(&app.graph_chars.empty) {
+                continue;
+            }
+            if let Some(cell) = buf.cell_mut((area.x + col as u16, area.y + row as u16)) {
+                cell.set_char(mark).set_style(style);
+            }
+        }
+    }
+}
+
+/// --cumulative: 本次运行累计字节数随时间增长的迷你图，首行为刻度标签
+fn draw_cumulative(frame: &mut Frame, area: Rect, app: &App, totals: &VecDeque<u64>, graph_color: Color) {
+    if area.height < 2 {
+        return;
+    }
+    let newest = totals.front().copied().unwrap_or(0);
+    let label = format!("Σ {} this session", stats::format_bytes(newest, app.unit));
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(label, Style::default().fg(Color::DarkGray)))),
+        Rect { height: 1, ..area },
+    );
+
+    // 累计值单调递增，最新值即为最大值，直接作为缩放上限
+    let values: VecDeque<f64> = totals.iter().map(|&t| t as f64).collect();
+    let graph_area = Rect {
+        y: area.y + 1,
+        height: area.height - 1,
+        ..area
+    };
+    let lines = graph::render_graph(
+        &values,
+        graph_area.width as usize,
+        graph_area.height as usize,
+        (newest as f64).max(1.0),
+        &app.graph_chars,
+        0.0,
+    );
+    let style = Style::default().fg(graph_color).add_modifier(Modifier::DIM);
+    let styled: Vec<Line> = lines
+        .into_iter()
+        .map(|l| Line::from(Span::styled(l, style)))
         .collect();
+    frame.render_widget(Paragraph::new(styled), graph_area);
+}
 
-    frame.render_widget(Paragraph::new(styled_lines), area);
+/// 同色系的暗色版本: RGB 颜色亮度减半，命名颜色退回 DarkGray
+fn dim_variant(color: Color) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => Color::Rgb(r / 2, g / 2, b / 2),
+        _ => Color::DarkGray,
+    }
+}
+
+/// --fade 时保持全亮的最新列数
+const FADE_RECENT_COLS: usize = 8;
+/// --fade 时最旧一列的亮度比例
+const FADE_MIN_BRIGHTNESS: f64 = 0.35;
+
+/// --fade: 按列龄 (`age` = 0 为最新列) 调暗样式
+///
+/// RGB 颜色按比例缩放亮度；命名颜色无法缩放，较旧的一半改用 DIM 修饰。
+fn fade_style(style: Style, age: usize, width: usize) -> Style {
+    if age < FADE_RECENT_COLS {
+        return style;
+    }
+    let span = width.saturating_sub(FADE_RECENT_COLS).max(1) as f64;
+    let t = ((age - FADE_RECENT_COLS) as f64 / span).min(1.0);
+    let factor = 1.0 - t * (1.0 - FADE_MIN_BRIGHTNESS);
+    match style.fg {
+        Some(Color::Rgb(r, g, b)) => {
+            let scale = |c: u8| (c as f64 * factor).round() as u8;
+            style.fg(Color::Rgb(scale(r), scale(g), scale(b)))
+        }
+        _ if t > 0.5 => style.add_modifier(Modifier::DIM),
+        _ => style,
+    }
+}
+
+/// 热力渐变色: `frac` 为 0.0 (底部) ~ 1.0 (顶部)
+fn heat_color(frac: f64) -> Color {
+    let frac = frac.clamp(0.0, 1.0);
+    let (from, to, t) = if frac < 0.5 {
+        ((0x00, 0xd7, 0x00), (0xff, 0xd7, 0x00), frac * 2.0)
+    } else {
+        ((0xff, 0xd7, 0x00), (0xff, 0x00, 0x00), (frac - 0.5) * 2.0)
+    };
+    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    Color::Rgb(lerp(from.0, to.0), lerp(from.1, to.1), lerp(from.2, to.2))
+}
+
+/// 在最新一列柱子的正上方标注当前数值
+///
+/// 标签右对齐到最右列，只放在完全空白的行上，避免遮挡旧数据；放不下时不显示。
+fn draw_live_value(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    lines: &[String],
+    history: &VecDeque<f64>,
+    graph_color: Color,
+) {
+    let Some(&current) = history.front() else {
+        return;
+    };
+    let text = stats::format_speed_unit(current, app.unit);
+    let text_width = text.chars().count();
+    let width = area.width as usize;
+    if text_width > width {
+        return;
+    }
+
+    // 最新一列最高的非空行
+    let top = lines
+        .iter()
+        .position(|l| l.chars().last().is_some_and(|c| c != app.graph_chars.empty))
+        .unwrap_or(lines.len());
+
+    // 从柱顶往上找一行，标签覆盖的格子全是空白
+    let start_col = width - text_width;
+    let row = (0..top).rev().find(|&r| {
+        lines[r]
+            .chars()
+            .skip(start_col)
+            .all(|c| c == app.graph_chars.empty)
+    });
+    if let Some(row) = row {
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                text,
+                Style::default().fg(graph_color).add_modifier(Modifier::BOLD),
+            ))),
+            Rect {
+                x: area.x + start_col as u16,
+                y: area.y + row as u16,
+                width: text_width as u16,
+                height: 1,
+            },
+        );
+    }
 }
 
 // ─── Stats ─────────────────────────────────────────────────
 
-fn draw_stats(frame: &mut Frame, area: Rect, stats: &TrafficStats, emoji: bool, unit: Unit) {
-    let stat_lines = format_stats_lines(stats, emoji, unit);
+/// 统计栏的一行: 标签右对齐、数值左对齐，由 [`layout_stat_rows`] 按最长标签统一排版
+struct StatRow {
+    /// emoji 模式下的行首图标 ("" = 无图标，留出同样宽度)
+    icon: &'static str,
+    label: String,
+    value: String,
+    label_style: Style,
+    value_style: Style,
+}
+
+impl StatRow {
+    /// 主统计行的样式: 青色粗体标签 + 白色数值
+    fn new(icon: &'static str, label: impl Into<String>, value: String) -> Self {
+        Self {
+            icon,
+            label: label.into(),
+            value,
+            label_style: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            value_style: Style::default().fg(Color::White),
+        }
+    }
+}
+
+/// 排版统计行: 标签列宽度取所有行中最长的标签，新增字段时无需手工补空格对齐
+fn layout_stat_rows(rows: Vec<StatRow>, emoji: bool) -> Vec<Line<'static>> {
+    let label_width = rows.iter().map(|r| r.label.chars().count()).max().unwrap_or(0);
+    rows.into_iter()
+        .map(|row| {
+            let pad = " ".repeat(label_width - row.label.chars().count());
+            let prefix = match (emoji, row.icon) {
+                (false, _) => String::new(),
+                // 图标统一占 2 列宽，再接一个空格
+                (true, "") => "   ".to_string(),
+                (true, icon) => format!("{icon} "),
+            };
+            Line::from(vec![
+                Span::styled(format!("{prefix}{pad}{}: ", row.label), row.label_style),
+                Span::styled(row.value, row.value_style),
+            ])
+        })
+        .collect()
+}
+
+fn draw_stats(frame: &mut Frame, area: Rect, rows: Vec<StatRow>, emoji: bool) {
+    let stat_lines = layout_stat_rows(rows, emoji);
     let stat_count = stat_lines.len() as u16;
 
     // 底部对齐
@@ -406,74 +1602,283 @@ fn draw_stats(frame: &mut Frame, area: Rect, stats: &TrafficStats, emoji: bool,
     }
 }
 
-fn format_stats_lines(st: &TrafficStats, emoji: bool, unit: Unit) -> Vec<Line<'static>> {
-    let label_style = Style::default()
-        .fg(Color::Cyan)
-        .add_modifier(Modifier::BOLD);
-    let value_style = Style::default().fg(Color::White);
+/// 趋势判定的死区: 当前速率与平均值相差不超过平均值的该比例时视为持平，避免箭头来回跳
+const TREND_DEADBAND: f64 = 0.1;
 
-    if emoji {
-        vec![
-            Line::from(vec![
-                Span::styled("⚡ Curr: ", label_style),
-                Span::styled(stats::format_speed_unit(st.current, unit), value_style),
-            ]),
-            Line::from(vec![
-                Span::styled("📊  Avg: ", label_style),
-                Span::styled(stats::format_speed_unit(st.average, unit), value_style),
-            ]),
-            Line::from(vec![
-                Span::styled("📏  Min: ", label_style),
-                Span::styled(stats::format_speed_unit(st.minimum, unit), value_style),
-            ]),
-            Line::from(vec![
-                Span::styled("🚀  Max: ", label_style),
-                Span::styled(stats::format_speed_unit(st.maximum, unit), value_style),
-            ]),
-            Line::from(vec![
-                Span::styled("📦  Ttl: ", label_style),
-                Span::styled(stats::format_bytes(st.total), value_style),
-            ]),
-        ]
+/// Curr 前的趋势箭头: 高于平均 ▲ / 低于平均 ▼ / 持平 ▬
+fn trend_arrow(st: &TrafficStats) -> char {
+    let band = st.average * TREND_DEADBAND;
+    if st.current > st.average + band {
+        '\u{25b2}'
+    } else if st.current < st.average - band {
+        '\u{25bc}'
     } else {
-        vec![
-            Line::from(vec![
-                Span::styled("Curr: ", label_style),
-                Span::styled(stats::format_speed_unit(st.current, unit), value_style),
-            ]),
-            Line::from(vec![
-                Span::styled(" Avg: ", label_style),
-                Span::styled(stats::format_speed_unit(st.average, unit), value_style),
-            ]),
-            Line::from(vec![
-                Span::styled(" Min: ", label_style),
-                Span::styled(stats::format_speed_unit(st.minimum, unit), value_style),
-            ]),
-            Line::from(vec![
-                Span::styled(" Max: ", label_style),
-                Span::styled(stats::format_speed_unit(st.maximum, unit), value_style),
-            ]),
-            Line::from(vec![
-                Span::styled(" Ttl: ", label_style),
-                Span::styled(stats::format_bytes(st.total), value_style),
-            ]),
-        ]
+        '\u{25ac}'
     }
 }
 
+fn format_stats_rows(
+    st: &TrafficStats,
+    unit: Unit,
+    unit_scale: Option<f64>,
+    exact_total: bool,
+    thousands_sep: Option<char>,
+) -> Vec<StatRow> {
+    // t 键: Ttl 在易读格式与精确字节数之间切换
+    let total = if exact_total {
+        stats::format_bytes_grouped(st.total, thousands_sep)
+    } else {
+        stats::format_bytes(st.total, unit)
+    };
+
+    let speed = |v: f64| format_panel_speed(v, unit, unit_scale);
+    let curr = format!("{} {}", trend_arrow(st), speed(st.current));
+    vec![
+        StatRow::new("⚡", "Curr", curr),
+        StatRow::new("📊", "Avg", speed(st.average)),
+        StatRow::new("📏", "Min", speed(st.minimum)),
+        StatRow::new("🚀", "Max", speed(st.maximum)),
+        StatRow::new("📦", "Ttl", total),
+    ]
+}
+
+/// 面板内的速率: `unit_scale` 为 Some 时按刻度固定前缀 (--fixed-unit)，否则按数值自动选择
+fn format_panel_speed(bytes_per_sec: f64, unit: Unit, unit_scale: Option<f64>) -> String {
+    match unit_scale {
+        Some(scale) => stats::format_speed_scaled(bytes_per_sec, unit, scale),
+        None => stats::format_speed_unit(bytes_per_sec, unit),
+    }
+}
+
+/// --recent-max: 最近 N 秒内的最高速率 (突发移出窗口后回落)
+fn recent_max_row(app: &App, view: &DeviceView, flow: Flow, unit_scale: Option<f64>) -> Option<StatRow> {
+    let secs = app.recent_max?;
+    let (recv, sent) = view.engine.recent_max()?;
+    let speed = match flow {
+        Flow::Incoming => recv,
+        Flow::Outgoing => sent,
+    };
+    Some(StatRow::new("📈", format!("Max{secs}s"), format_panel_speed(speed, app.unit, unit_scale)))
+}
+
+/// --window-total: 最近 N 秒内传输的字节数
+fn window_total_row(app: &App, view: &DeviceView, flow: Flow) -> Option<StatRow> {
+    let secs = app.window_total?;
+    let (recv, sent) = view.engine.window_total()?;
+    let bytes = match flow {
+        Flow::Incoming => recv,
+        Flow::Outgoing => sent,
+    };
+    Some(StatRow::new("🪟", format!("{secs}s"), stats::format_bytes(bytes, app.unit)))
+}
+
+/// --eta-target: 按平均收速率估算剩余时间，达到目标后显示 "done."
+fn eta_row(app: &App, view: &DeviceView) -> Option<StatRow> {
+    let target = app.eta_target?;
+    let (received, _) = view.engine.session_totals();
+    let avg = view.engine.incoming.average;
+    let text = if received as f64 >= target {
+        "done.".to_string()
+    } else if avg > 0.0 {
+        let secs = ((target - received as f64) / avg).ceil() as u64;
+        stats::format_hms(secs)
+    } else {
+        "--:--:--".to_string()
+    };
+    Some(StatRow::new("⏳", "ETA", text))
+}
+
+/// --pcap: 当前网卡正在抓包时，追加各协议的当前速率
+fn proto_rows(app: &App, view: &DeviceView, flow: Flow, unit_scale: Option<f64>) -> Vec<StatRow> {
+    let Some(proto) = app.proto.as_ref().filter(|p| p.device == view.info.name) else {
+        return vec![];
+    };
+    proto
+        .engines
+        .iter()
+        .map(|(name, engine)| StatRow {
+            icon: "",
+            label: name.to_string(),
+            value: format_panel_speed(flow.stats(engine).current, app.unit, unit_scale),
+            label_style: Style::default().fg(Color::Cyan),
+            value_style: Style::default().fg(Color::Gray),
+        })
+        .collect()
+}
+
 // ─── Help / Error ──────────────────────────────────────────
 
-fn draw_help(frame: &mut Frame, area: Rect, emoji: bool, bar_style: BarStyle) {
-    let help_text = if emoji {
+/// 底部状态栏 (--status): 按配置的字段顺序拼接汇总信息
+/// --top 进程表: 按当前 TCP 总速率排序的前 N 个进程
+fn draw_top(frame: &mut Frame, area: Rect, app: &App) {
+    if area.height < 2 {
+        return;
+    }
+    let sep_width = (area.width as usize).min(120);
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            "=".repeat(sep_width),
+            Style::default().fg(Color::Cyan),
+        ))),
+        Rect { height: 1, ..area },
+    );
+
+    let rows = &app.top_rows[..app.top_rows.len().min(app.top_n)];
+    frame.render_widget(
+        process_table(app, rows),
+        Rect {
+            y: area.y + 1,
+            height: area.height - 1,
+            ..area
+        },
+    );
+}
+
+/// 进程页 (p 键): 标题行 + 所有有 TCP 流量的进程，按 o 键选择的列排序
+fn draw_processes(frame: &mut Frame, area: Rect, app: &App) {
+    if area.height < 2 {
+        return;
+    }
+    let title = format!(
+        "{}Processes by TCP throughput ({}) - sorted by {} (o: sort, p: close)",
+        if app.emoji { "📊 " } else { "" },
+        app.top_rows.len(),
+        app.process_sort.label()
+    );
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(title, Style::default().add_modifier(Modifier::BOLD)))),
+        Rect { height: 1, ..area },
+    );
+    frame.render_widget(
+        process_table(app, &app.top_rows),
+        Rect {
+            y: area.y + 1,
+            height: area.height - 1,
+            ..area
+        },
+    );
+}
+
+/// 进程表 (--top 面板与进程页共用)，排序所依据的列名后加标记
+fn process_table<'a>(app: &App, rows: &'a [ProcessRate]) -> Table<'a> {
+    let marker = if app.unicode { " \u{25bc}" } else { " *" };
+    let columns = [
+        ("PID", ProcessSort::Pid),
+        ("Process", ProcessSort::Name),
+        ("In", ProcessSort::Recv),
+        ("Out", ProcessSort::Sent),
+        ("Total", ProcessSort::Total),
+    ];
+    let header = Row::new(columns.map(|(name, sort)| {
+        if sort == app.process_sort {
+            format!("{name}{marker}")
+        } else {
+            name.to_string()
+        }
+    }))
+    .style(Style::default().add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = if rows.is_empty() {
+        vec![Row::new(["", "(no TCP traffic)", "", "", ""]).style(Style::default().fg(Color::DarkGray))]
+    } else {
+        rows.iter()
+            .map(|p| {
+                Row::new([
+                    Cell::from(p.pid.to_string()),
+                    Cell::from(p.name.clone()),
+                    Cell::from(stats::format_speed_unit(p.recv, app.unit)).style(Style::default().fg(app.in_color)),
+                    Cell::from(stats::format_speed_unit(p.sent, app.unit)).style(Style::default().fg(app.out_color)),
+                    Cell::from(stats::format_speed_unit(p.total(), app.unit)),
+                ])
+            })
+            .collect()
+    };
+    let widths = [
+        Constraint::Length(8),
+        Constraint::Min(16),
+        Constraint::Length(16),
+        Constraint::Length(16),
+        Constraint::Length(16),
+    ];
+    Table::new(rows, widths).header(header)
+}
+
+fn draw_status(frame: &mut Frame, area: Rect, app: &App) {
+    let parts: Vec<String> = app
+        .status_fields
+        .iter()
+        .filter_map(|field| match field {
+            StatusField::Total => Some(format!(
+                "\u{3a3} \u{2193}{} \u{2191}{}",
+                stats::format_speed_unit(app.total_in, app.unit),
+                stats::format_speed_unit(app.total_out, app.unit),
+            )),
+            StatusField::Devices => {
+                let active = app
+                    .views
+                    .iter()
+                    .filter(|v| v.engine.incoming.current > 0.0 || v.engine.outgoing.current > 0.0)
+                    .count();
+                Some(format!("{active}/{} active", app.views.len()))
+            }
+            StatusField::Elapsed => {
+                let secs = app.started.elapsed().as_secs();
+                Some(stats::format_hms(secs))
+            }
+            StatusField::Unit => Some(app.unit.label().to_string()),
+            StatusField::Frozen => app.graph_frozen.then(|| "FROZEN".to_string()),
+            // 取当前网卡的实际值: --device-interval 与平均窗口的最少采样数都可能改变它们
+            StatusField::Timing => app.current_view().map(|v| {
+                format!(
+                    "t={}ms a={:.0}s",
+                    v.interval.as_millis(),
+                    v.engine.avg_window_secs()
+                )
+            }),
+            // 收发占比: 按当前网卡的平均速率，空闲时显示 —
+            StatusField::Ratio => Some(
+                match app.current_view().and_then(|v| v.engine.in_share()) {
+                    Some(share) => {
+                        let pct_in = (share * 100.0).round() as u32;
+                        format!("{pct_in}% in / {}% out", 100 - pct_in)
+                    }
+                    None => "\u{2014} in / \u{2014} out".to_string(),
+                },
+            ),
+        })
+        .collect();
+    let text = format!(" {}", parts.join(" | "));
+
+    let style = match app.bar_style {
+        BarStyle::Fill | BarStyle::Color => Style::default().bg(Color::DarkGray).fg(Color::White),
+        BarStyle::Plain => Style::default().fg(Color::Gray),
+    };
+    let display = if app.bar_style == BarStyle::Fill {
+        pad_to_width(&text, area.width as usize)
+    } else {
+        text
+    };
+    frame.render_widget(Paragraph::new(Line::from(Span::styled(display, style))), area);
+}
+
+/// 底部帮助栏
+///
+/// 按键语义: 方向键切换上/下一个网卡，Tab 循环切换，
+/// Enter 预留给确认 / 放大 (列表视图)，目前不做任何事，因此不在帮助栏中列出。
+fn draw_help(frame: &mut Frame, area: Rect, emoji: bool, bar_style: BarStyle, notice: Option<&str>) {
+    // 短提示 (如 c 键复制结果) 临时替换帮助文本
+    let notice_text = notice.map(|n| format!(" {n}"));
+    let help_text = if let Some(ref n) = notice_text {
+        n.as_str()
+    } else if emoji {
         #[cfg(target_os = "windows")]
-        { " ⬅️/➡️ Switch Device | 🚪 q Quit | 💡 Loopback: --npcap" }
+        { " ⬅️/➡️ Switch Device | ↹ Tab Cycle | 📐 u Unit | 🚪 q Quit | 💡 Loopback: --npcap" }
         #[cfg(not(target_os = "windows"))]
-        { " ⬅️/➡️ Switch Device | 🚪 q Quit" }
+        { " ⬅️/➡️ Switch Device | ↹ Tab Cycle | 📐 u Unit | 🚪 q Quit" }
     } else {
         #[cfg(target_os = "windows")]
-        { " \u{2190}/\u{2192} Switch Device | q Quit | Loopback: --npcap" }
+        { " \u{2190}/\u{2192} Switch Device | Tab Cycle | u Unit | q Quit | Loopback: --npcap" }
         #[cfg(not(target_os = "windows"))]
-        { " \u{2190}/\u{2192} Switch Device | q Quit" }
+        { " \u{2190}/\u{2192} Switch Device | Tab Cycle | u Unit | q Quit" }
     };
 
     let width = area.width as usize;
@@ -497,6 +1902,91 @@ fn draw_help(frame: &mut Frame, area: Rect, emoji: bool, bar_style: BarStyle) {
     frame.render_widget(Paragraph::new(vec![help]), area);
 }
 
+/// 下次采样倒计时 (--countdown): 帮助栏右端的 "next in 0.3s"
+fn draw_countdown(frame: &mut Frame, area: Rect, app: &App) {
+    let text = format!(" next in {:.1}s ", app.next_sample_in.as_secs_f64());
+    let width = text.chars().count() as u16;
+    // 帮助栏太窄时不显示，避免盖住按键说明
+    if area.width < width * 3 {
+        return;
+    }
+    let style = match app.bar_style {
+        BarStyle::Fill | BarStyle::Color => Style::default().bg(Color::White).fg(Color::DarkGray),
+        BarStyle::Plain => Style::default().fg(Color::DarkGray),
+    };
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(text, style))),
+        Rect {
+            x: area.x + area.width - width,
+            width,
+            ..area
+        },
+    );
+}
+
+/// 紧凑视图: 首行网卡名，其余行上下分给收 / 发，每个方向为 "速率 + 迷你图"
+fn draw_compact(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(view) = app.current_view() else {
+        return;
+    };
+    // 紧凑视图没有帮助栏，--confirm-quit 的提示显示在标题行
+    let title = if app.confirming_quit {
+        "Quit? (y/n)".to_string()
+    } else {
+        format!("{} ({}/{})", view.info.name, app.current_idx + 1, app.views.len())
+    };
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            title,
+            Style::default().add_modifier(Modifier::BOLD),
+        ))),
+        Rect { height: 1, ..area },
+    );
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Fill(1), Constraint::Fill(1)])
+        .split(area);
+    for (flow, row) in [(Flow::Incoming, rows[1]), (Flow::Outgoing, rows[2])] {
+        draw_rate_sparkline(frame, row, app, view, flow, None);
+    }
+}
+
+/// 一行 "箭头 + 当前速率 + 迷你图" (紧凑视图与总览共用)
+///
+/// `max` 为迷你图的缩放上限，None 时按自身数据的最大值。
+fn draw_rate_sparkline(frame: &mut Frame, row: Rect, app: &App, view: &DeviceView, flow: Flow, max: Option<u64>) {
+    let (arrow, color) = match flow {
+        Flow::Incoming => ('\u{2193}', app.in_color),
+        Flow::Outgoing => ('\u{2191}', app.out_color),
+    };
+    let label = format!(
+        "{arrow}{:>13} ",
+        stats::format_speed_unit(flow.stats(&view.engine).current, app.unit)
+    );
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(label.chars().count() as u16), Constraint::Min(0)])
+        .split(row);
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(label, Style::default().fg(color)))),
+        cols[0],
+    );
+    // 迷你图从左到右由旧到新，只取能放下的最新若干个点
+    let data: Vec<u64> = flow
+        .history(&view.engine)
+        .iter()
+        .take(cols[1].width as usize)
+        .rev()
+        .map(|&v| v as u64)
+        .collect();
+    let mut sparkline = Sparkline::default().data(&data).style(Style::default().fg(color));
+    if let Some(max) = max {
+        sparkline = sparkline.max(max);
+    }
+    frame.render_widget(sparkline, cols[1]);
+}
+
 fn draw_too_small(frame: &mut Frame, area: Rect, emoji: bool) {
     let msg = if emoji {
         "😭 Terminal too small! 📌"
@@ -521,3 +2011,31 @@ fn draw_too_small(frame: &mut Frame, area: Rect, emoji: bool) {
         },
     );
 }
+
+fn draw_no_devices(frame: &mut Frame, area: Rect, emoji: bool) {
+    let msg = if emoji {
+        "😶 No network interfaces found 🔌"
+    } else {
+        "No network interfaces found"
+    };
+    let hint = "Run with --debug-info to see what was detected";
+    let msg_width = msg.chars().count() as u16;
+    let hint_width = hint.chars().count() as u16;
+    let y = area.height.saturating_sub(2) / 2;
+    let lines = [
+        (msg, msg_width, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+        (hint, hint_width, Style::default().fg(Color::DarkGray)),
+    ];
+    for (i, (text, text_width, style)) in lines.into_iter().enumerate() {
+        let w = text_width.min(area.width);
+        frame.render_widget(
+            Paragraph::new(vec![Line::from(Span::styled(text, style))]),
+            Rect {
+                x: area.x + (area.width - w) / 2,
+                y: area.y + y + i as u16,
+                width: w,
+                height: 1,
+            },
+        );
+    }
+}